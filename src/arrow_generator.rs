@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{unique_names, write_to_string};
+
+/// renders the arrow `DataType` of a single column, spelled like arrow-rs (`Int64`,
+/// `Timestamp(Microsecond, None)`, ...).
+fn arrow_type(sql_type: &SqlType) -> String {
+    match sql_type {
+        SqlType::Integer => "Int32".to_string(),
+        SqlType::BigInt => "Int64".to_string(),
+        SqlType::Float | SqlType::Percentage => "Float64".to_string(),
+        SqlType::Decimal(precision, scale) => {
+            format!("Decimal128({}, {})", precision.max(&1), scale)
+        }
+        SqlType::Boolean => "Boolean".to_string(),
+        SqlType::Date => "Date32".to_string(),
+        SqlType::Datetime => "Timestamp(Microsecond, None)".to_string(),
+        SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet | SqlType::Json => {
+            "Utf8".to_string()
+        }
+    }
+}
+
+/// generates a textual arrow (and parquet-compatible) schema from headers and inferred types:
+/// one `name: DataType` line per column, where columns whose `nullability` entry is false are
+/// marked `not null`. names are the trimmed headers, which arrow accepts unchanged, with
+/// repeated ones made unique (`id, id` becomes `id, id_2`).
+pub fn generate_arrow_schema(
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| generate_arrow_schema_to(writer, headers, types, nullability))
+}
+
+/// writes the schema of `generate_arrow_schema` to `writer`.
+pub fn generate_arrow_schema_to<W: Write>(
+    writer: &mut W,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    let names = unique_names(headers.iter().map(|header| header.trim().to_string()));
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b"\n")?;
+        }
+        write!(writer, "{}: {}", name, arrow_type(&types[i]))?;
+        if !nullability[i] {
+            writer.write_all(b" not null")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_arrow_schema() {
+        let headers = StringRecord::from(vec!["id", "count", " name ", "price", "seen_at", "day"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Integer,
+            SqlType::Varchar(20),
+            SqlType::Decimal(6, 2),
+            SqlType::Datetime,
+            SqlType::Date,
+        ];
+        let nullability = vec![false, true, true, false, true, false];
+        assert_eq!(
+            generate_arrow_schema(&headers, &types, &nullability),
+            "id: Int64 not null\ncount: Int32\nname: Utf8\nprice: Decimal128(6, 2) not null\n\
+             seen_at: Timestamp(Microsecond, None)\nday: Date32 not null"
+        );
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        assert_eq!(
+            generate_arrow_schema(&headers, &types, &[true; 3]),
+            "id: Int32\nname: Utf8\nid_2: Int32"
+        );
+    }
+}
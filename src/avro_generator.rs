@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{json_string, to_pascal_case, unique_names, write_to_string};
+
+/// turns a csv header into a valid avro name (`[A-Za-z_][A-Za-z0-9_]*`). every other character
+/// becomes an underscore, and a leading digit gets an underscore prefix.
+fn avro_field_name(header: &str) -> String {
+    let name: String = header
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// renders the avro type of a single column value.
+fn avro_type(sql_type: &SqlType) -> String {
+    match sql_type {
+        SqlType::Integer => "\"int\"".to_string(),
+        SqlType::BigInt => "\"long\"".to_string(),
+        SqlType::Float | SqlType::Percentage => "\"double\"".to_string(),
+        SqlType::Decimal(precision, scale) => format!(
+            "{{\"type\": \"bytes\", \"logicalType\": \"decimal\", \"precision\": {}, \"scale\": {}}}",
+            precision.max(&1),
+            scale
+        ),
+        SqlType::Boolean => "\"boolean\"".to_string(),
+        SqlType::Date => "{\"type\": \"int\", \"logicalType\": \"date\"}".to_string(),
+        SqlType::Datetime => {
+            "{\"type\": \"long\", \"logicalType\": \"timestamp-millis\"}".to_string()
+        }
+        SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet | SqlType::Json => {
+            "\"string\"".to_string()
+        }
+    }
+}
+
+/// generates an avro record schema (as json) from a record name, headers, and inferred types.
+/// columns whose `nullability` entry is true become `["null", T]` unions defaulting to null.
+pub fn generate_avro_schema(
+    record_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| {
+        generate_avro_schema_to(writer, record_name, headers, types, nullability)
+    })
+}
+
+/// writes the schema of `generate_avro_schema` to `writer`.
+pub fn generate_avro_schema_to<W: Write>(
+    writer: &mut W,
+    record_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writer.write_all(b"{\n")?;
+    writer.write_all(b"  \"type\": \"record\",\n")?;
+    writeln!(
+        writer,
+        "  \"name\": {},",
+        json_string(&to_pascal_case(record_name))
+    )?;
+
+    writer.write_all(b"  \"fields\": [\n")?;
+    // repeated headers (`id, name, id`) would declare the same field twice.
+    let field_names = unique_names(headers.iter().map(avro_field_name));
+    for (i, field_name) in field_names.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",\n")?;
+        }
+        let name = json_string(field_name);
+        if nullability[i] {
+            write!(
+                writer,
+                "    {{\"name\": {}, \"type\": [\"null\", {}], \"default\": null}}",
+                name,
+                avro_type(&types[i])
+            )?;
+        } else {
+            write!(
+                writer,
+                "    {{\"name\": {}, \"type\": {}}}",
+                name,
+                avro_type(&types[i])
+            )?;
+        }
+    }
+    writer.write_all(b"\n  ]\n")?;
+
+    writer.write_all(b"}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_avro_schema_unions_and_logical_types() {
+        let headers = StringRecord::from(vec!["id", "age", "born", "seen at", "name"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Integer,
+            SqlType::Date,
+            SqlType::Datetime,
+            SqlType::Varchar(10),
+        ];
+        let nullability = vec![false, true, false, true, false];
+        let schema = generate_avro_schema("people", &headers, &types, &nullability);
+
+        let json: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(json["type"], "record");
+        assert_eq!(json["name"], "People");
+        let fields = json["fields"].as_array().unwrap();
+        assert_eq!(fields[0], serde_json::json!({"name": "id", "type": "long"}));
+        assert_eq!(fields[1]["type"], serde_json::json!(["null", "int"]));
+        assert_eq!(fields[1]["default"], serde_json::Value::Null);
+        assert_eq!(
+            fields[2]["type"],
+            serde_json::json!({"type": "int", "logicalType": "date"})
+        );
+        assert_eq!(fields[3]["name"], "seen_at");
+        assert_eq!(fields[3]["type"][1]["logicalType"], "timestamp-millis");
+        assert_eq!(fields[4]["type"], "string");
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let schema = generate_avro_schema("people", &headers, &types, &[false; 3]);
+        let json: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        let names: Vec<&str> = json["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["id", "name", "id_2"]);
+    }
+
+    #[test]
+    fn test_avro_field_name() {
+        assert_eq!(avro_field_name(" unit price "), "unit_price");
+        assert_eq!(avro_field_name("2nd"), "_2nd");
+        assert_eq!(avro_field_name(""), "_");
+    }
+}
@@ -0,0 +1,207 @@
+use super::{SqlType, StringRecord};
+use crate::sql_generator::{Dialect, SqlOptions, generate_sql_with_options};
+
+/// an inferred table, as produced by running `infer_schema` on one csv file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSchema {
+    pub name: String,
+    pub headers: StringRecord,
+    pub types: Vec<SqlType>,
+}
+
+/// the order in which `generate_sql_batch` emits its `create table` statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchOrder {
+    /// emit tables in the order they were given.
+    #[default]
+    Input,
+    /// emit referenced tables before the tables whose `*_id` columns point at them.
+    References,
+}
+
+/// returns the index of the table a `*_id` column refers to, if any.
+/// `user_id` matches a table named `user` or `users` (case-insensitive).
+fn referenced_table(column: &str, tables: &[TableSchema]) -> Option<usize> {
+    let column = column.trim().to_lowercase();
+    let stem = column.strip_suffix("_id")?;
+    if stem.is_empty() {
+        return None;
+    }
+    let plural = format!("{}s", stem);
+    tables.iter().position(|table| {
+        let name = table.name.to_lowercase();
+        name == stem || name == plural
+    })
+}
+
+/// detects the `*_id -> table` relationships between the given tables.
+/// the result holds, for each table, the indices of the other tables it references.
+/// self references (e.g. `users.user_id`) are ignored.
+pub fn find_references(tables: &[TableSchema]) -> Vec<Vec<usize>> {
+    tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| {
+            let mut referenced: Vec<usize> = Vec::new();
+            for header in table.headers.iter() {
+                if let Some(target) = referenced_table(header, tables)
+                    && target != i
+                    && !referenced.contains(&target)
+                {
+                    referenced.push(target);
+                }
+            }
+            referenced
+        })
+        .collect()
+}
+
+/// sorts tables so that referenced tables come before the tables referencing them.
+/// ties keep the input order. cycles are broken by emitting the earliest remaining table
+/// of the cycle first; a warning describing each break is returned alongside the order.
+pub fn topological_order(tables: &[TableSchema]) -> (Vec<usize>, Vec<String>) {
+    let references = find_references(tables);
+    let mut emitted = vec![false; tables.len()];
+    let mut order = Vec::with_capacity(tables.len());
+    let mut warnings = Vec::new();
+
+    while order.len() < tables.len() {
+        let ready = (0..tables.len())
+            .find(|&i| !emitted[i] && references[i].iter().all(|&target| emitted[target]));
+
+        let next = match ready {
+            Some(i) => i,
+            None => {
+                // every remaining table waits on another remaining table: a cycle.
+                let i = (0..tables.len())
+                    .find(|&i| !emitted[i])
+                    .expect("at least one table is left to emit");
+                let pending: Vec<&str> = references[i]
+                    .iter()
+                    .filter(|&&target| !emitted[target])
+                    .map(|&target| tables[target].name.as_str())
+                    .collect();
+                warnings.push(format!(
+                    "cyclic reference: \"{}\" is created before {}",
+                    tables[i].name,
+                    pending
+                        .iter()
+                        .map(|name| format!("\"{}\"", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                i
+            }
+        };
+
+        emitted[next] = true;
+        order.push(next);
+    }
+
+    (order, warnings)
+}
+
+/// generates the `create table` statements for several tables, separated by blank lines.
+/// with `BatchOrder::References`, tables are topologically sorted by their `*_id` columns and
+/// any cycle that had to be broken is reported as a leading `-- warning:` comment.
+pub fn generate_sql_batch(
+    tables: &[TableSchema],
+    order: BatchOrder,
+    dialect: Dialect,
+    options: &SqlOptions,
+) -> String {
+    let (indices, warnings) = match order {
+        BatchOrder::Input => ((0..tables.len()).collect(), Vec::new()),
+        BatchOrder::References => topological_order(tables),
+    };
+
+    let mut sql = String::new();
+    for warning in &warnings {
+        sql.push_str(&format!("-- warning: {}\n", warning));
+    }
+
+    let statements: Vec<String> = indices
+        .into_iter()
+        .map(|i| {
+            let table = &tables[i];
+            generate_sql_with_options(&table.name, &table.headers, &table.types, dialect, options)
+        })
+        .collect();
+    sql.push_str(&statements.join("\n\n"));
+
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infer_schema;
+    use std::io::Cursor;
+
+    fn table(name: &str, csv_data: &str) -> TableSchema {
+        let (headers, types) = infer_schema(Cursor::new(csv_data)).unwrap();
+        TableSchema {
+            name: name.to_string(),
+            headers,
+            types,
+        }
+    }
+
+    #[test]
+    fn test_referenced_tables_are_emitted_first() {
+        let tables = vec![
+            table("orders", "id,user_id,total\n1,10,9.99\n2,11,5.00"),
+            table("users", "id,name\n10,Alice\n11,Bob"),
+        ];
+
+        let (order, warnings) = topological_order(&tables);
+        assert_eq!(order, vec![1, 0]);
+        assert!(warnings.is_empty());
+
+        let sql = generate_sql_batch(
+            &tables,
+            BatchOrder::References,
+            Dialect::Generic,
+            &SqlOptions::default(),
+        );
+        let users_pos = sql.find("CREATE TABLE \"users\"").unwrap();
+        let orders_pos = sql.find("CREATE TABLE \"orders\"").unwrap();
+        assert!(users_pos < orders_pos);
+    }
+
+    #[test]
+    fn test_input_order_is_kept_by_default() {
+        let tables = vec![
+            table("orders", "id,user_id\n1,10"),
+            table("users", "id,name\n10,Alice"),
+        ];
+        let sql = generate_sql_batch(
+            &tables,
+            BatchOrder::default(),
+            Dialect::Generic,
+            &SqlOptions::default(),
+        );
+        assert!(sql.starts_with("CREATE TABLE \"orders\""));
+    }
+
+    #[test]
+    fn test_cycles_are_broken_with_a_warning() {
+        let tables = vec![
+            table("a", "id,b_id\n1,2"),
+            table("b", "id,a_id\n2,1"),
+            table("c", "id,c_id\n3,3"), // self reference only
+        ];
+        let (order, warnings) = topological_order(&tables);
+        assert_eq!(order, vec![2, 0, 1]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("\"a\" is created before \"b\""));
+
+        let sql = generate_sql_batch(
+            &tables,
+            BatchOrder::References,
+            Dialect::Generic,
+            &SqlOptions::default(),
+        );
+        assert!(sql.starts_with("-- warning: cyclic reference"));
+    }
+}
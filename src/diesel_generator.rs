@@ -0,0 +1,145 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::rust_generator::RUST_KEYWORDS;
+use crate::utils::{to_snake_case, unique_names, write_to_string};
+
+/// turns a csv header or table name into a snake_case identifier usable inside diesel's
+/// `table!` macro. a leading digit gets an underscore prefix and keywords an underscore suffix,
+/// since the macro does not accept raw identifiers.
+fn diesel_name(header: &str) -> String {
+    let name = to_snake_case(header);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// generates a diesel `table!` block (as found in `schema.rs`) from a table name, headers, and
+/// inferred types. nullable columns are wrapped in `Nullable<T>`, and a `#[sql_name = ...]`
+/// attribute keeps the original header whenever the column name had to be changed.
+/// `primary_key` names the key columns by their headers (several for a composite key); when it
+/// is empty the first column is used.
+pub fn generate_diesel_schema(
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    primary_key: &[&str],
+) -> String {
+    write_to_string(|writer| {
+        generate_diesel_schema_to(writer, table_name, headers, types, nullability, primary_key)
+    })
+}
+
+/// writes the schema of `generate_diesel_schema` to `writer`.
+pub fn generate_diesel_schema_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    primary_key: &[&str],
+) -> io::Result<()> {
+    let key_columns: Vec<String> = if primary_key.is_empty() {
+        vec![diesel_name(headers.get(0).unwrap_or("id"))]
+    } else {
+        primary_key
+            .iter()
+            .map(|header| diesel_name(header))
+            .collect()
+    };
+
+    writer.write_all(b"diesel::table! {\n")?;
+    writeln!(
+        writer,
+        "    {} ({}) {{",
+        diesel_name(table_name),
+        key_columns.join(", ")
+    )?;
+
+    // repeated headers (`id, name, id`) would declare the same column twice.
+    let column_names = unique_names(headers.iter().map(diesel_name));
+    for (i, header) in headers.iter().enumerate() {
+        let diesel_type = match &types[i] {
+            SqlType::Integer => "Integer",
+            SqlType::BigInt => "BigInt",
+            SqlType::Float | SqlType::Percentage => "Float",
+            SqlType::Decimal(..) => "Numeric",
+            SqlType::Boolean => "Bool",
+            SqlType::Date => "Date",
+            SqlType::Datetime => "Timestamp",
+            // diesel's `Inet` needs its `network-address` feature, so addresses stay text.
+            SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet => "Text",
+            SqlType::Json => "Jsonb",
+        };
+        let column_type = if nullability[i] {
+            format!("Nullable<{}>", diesel_type)
+        } else {
+            diesel_type.to_string()
+        };
+
+        let column_name = &column_names[i];
+        if column_name != header {
+            writeln!(writer, "        #[sql_name = {:?}]", header)?;
+        }
+        writeln!(writer, "        {} -> {},", column_name, column_type)?;
+    }
+
+    writer.write_all(b"    }\n}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_diesel_schema() {
+        let headers = StringRecord::from(vec!["order_id", "Customer Age", "type", "placed_at"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Integer,
+            SqlType::Char(3),
+            SqlType::Datetime,
+        ];
+        let nullability = vec![false, true, false, true];
+        let expected = r#"diesel::table! {
+    orders (order_id) {
+        order_id -> BigInt,
+        #[sql_name = "Customer Age"]
+        customer_age -> Nullable<Integer>,
+        #[sql_name = "type"]
+        type_ -> Text,
+        placed_at -> Nullable<Timestamp>,
+    }
+}"#;
+        assert_eq!(
+            generate_diesel_schema("orders", &headers, &types, &nullability, &[]),
+            expected
+        );
+
+        let generated = generate_diesel_schema("orders", &headers, &types, &nullability, &["type"]);
+        assert!(generated.contains("    orders (type_) {\n"));
+        let generated = generate_diesel_schema(
+            "orders",
+            &headers,
+            &types,
+            &nullability,
+            &["order_id", "type"],
+        );
+        assert!(generated.contains("    orders (order_id, type_) {\n"));
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let generated = generate_diesel_schema("people", &headers, &types, &[false; 3], &[]);
+        assert!(generated.contains("    people (id) {\n        id -> Integer,\n"));
+        assert!(generated.contains("        #[sql_name = \"id\"]\n        id_2 -> Integer,\n"));
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::python_generator::PkStrategy;
+use crate::utils::{to_pascal_case, to_snake_case, unique_names, write_to_string};
+
+/// renders the django model field for a column of `sql_type`, given its extra arguments.
+fn django_field(sql_type: &SqlType, mut args: Vec<String>) -> String {
+    let (field, type_args) = match sql_type {
+        SqlType::Integer => ("IntegerField", Vec::new()),
+        SqlType::BigInt => ("BigIntegerField", Vec::new()),
+        SqlType::Float | SqlType::Percentage => ("FloatField", Vec::new()),
+        SqlType::Decimal(precision, scale) => (
+            "DecimalField",
+            vec![
+                format!("max_digits={}", precision.max(&1)),
+                format!("decimal_places={}", scale),
+            ],
+        ),
+        SqlType::Char(len) | SqlType::Varchar(len) => {
+            ("CharField", vec![format!("max_length={}", len.max(&1))])
+        }
+        SqlType::Text => ("TextField", Vec::new()),
+        SqlType::Date => ("DateField", Vec::new()),
+        SqlType::Datetime => ("DateTimeField", Vec::new()),
+        SqlType::Boolean => ("BooleanField", Vec::new()),
+        SqlType::Inet => ("GenericIPAddressField", Vec::new()),
+        SqlType::Json => ("JSONField", Vec::new()),
+    };
+    args.splice(0..0, type_args);
+    format!("models.{}({})", field, args.join(", "))
+}
+
+/// generates a django model from a class name, headers, inferred types and a primary key
+/// strategy. nullable columns get `null=True, blank=True`, and a `db_column` keeps the original
+/// header whenever the field name had to be changed. a `CreateColumn` key becomes an
+/// `AutoField`, a `Composite` key a `CompositePrimaryKey` (django 5.2+), and without a key
+/// django adds its implicit `id` field.
+pub fn generate_django_model(
+    class_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    pk_strategy: &PkStrategy,
+) -> String {
+    write_to_string(|writer| {
+        generate_django_model_to(writer, class_name, headers, types, nullability, pk_strategy)
+    })
+}
+
+/// writes the model of `generate_django_model` to `writer`.
+pub fn generate_django_model_to<W: Write>(
+    writer: &mut W,
+    class_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    pk_strategy: &PkStrategy,
+) -> io::Result<()> {
+    writer.write_all(b"from django.db import models\n\n\n")?;
+    writeln!(
+        writer,
+        "class {}(models.Model):",
+        to_pascal_case(class_name)
+    )?;
+
+    let mut field_count = 0;
+    match pk_strategy {
+        PkStrategy::CreateColumn(pk_name) => {
+            writeln!(
+                writer,
+                "    {} = models.AutoField(primary_key=True)",
+                to_snake_case(pk_name)
+            )?;
+            field_count += 1;
+        }
+        PkStrategy::Composite(pk_names) => {
+            let key_fields: Vec<String> = pk_names
+                .iter()
+                .map(|pk_name| format!("{:?}", to_snake_case(pk_name)))
+                .collect();
+            writeln!(
+                writer,
+                "    pk = models.CompositePrimaryKey({})",
+                key_fields.join(", ")
+            )?;
+            field_count += 1;
+        }
+        PkStrategy::ExistingColumn(_) | PkStrategy::None => {}
+    }
+
+    // repeated headers (`id, name, id`) would declare the same attribute twice.
+    let field_names = unique_names(headers.iter().map(to_snake_case));
+    for (i, header) in headers.iter().enumerate() {
+        let field_name = &field_names[i];
+        // the created key replaces a csv column of the same name.
+        if let PkStrategy::CreateColumn(pk_name) = pk_strategy
+            && *field_name == to_snake_case(pk_name)
+        {
+            continue;
+        }
+
+        let mut args = Vec::new();
+        if matches!(pk_strategy, PkStrategy::ExistingColumn(_))
+            && pk_strategy.is_key_column(field_name)
+        {
+            args.push("primary_key=True".to_string());
+        } else if nullability[i] {
+            args.push("null=True".to_string());
+            args.push("blank=True".to_string());
+        }
+        if field_name != header {
+            args.push(format!("db_column={:?}", header));
+        }
+        writeln!(
+            writer,
+            "    {} = {}",
+            field_name,
+            django_field(&types[i], args)
+        )?;
+        field_count += 1;
+    }
+
+    if field_count == 0 {
+        writer.write_all(b"    pass\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_django_model_created_pk() {
+        let headers = StringRecord::from(vec!["Name", "age", "score", "joined", "active"]);
+        let types = vec![
+            SqlType::Varchar(40),
+            SqlType::Integer,
+            SqlType::Float,
+            SqlType::Date,
+            SqlType::Boolean,
+        ];
+        let nullability = vec![false, true, false, true, false];
+        let expected = r#"from django.db import models
+
+
+class Person(models.Model):
+    id = models.AutoField(primary_key=True)
+    name = models.CharField(max_length=40, db_column="Name")
+    age = models.IntegerField(null=True, blank=True)
+    score = models.FloatField()
+    joined = models.DateField(null=True, blank=True)
+    active = models.BooleanField()
+"#;
+        assert_eq!(
+            generate_django_model(
+                "person",
+                &headers,
+                &types,
+                &nullability,
+                &PkStrategy::CreateColumn("id".to_string())
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_generate_django_model_existing_pk() {
+        let headers = StringRecord::from(vec!["code", "label", "created_at", "price"]);
+        let types = vec![
+            SqlType::Char(3),
+            SqlType::Varchar(0),
+            SqlType::Datetime,
+            SqlType::Decimal(8, 2),
+        ];
+        let model = generate_django_model(
+            "currency",
+            &headers,
+            &types,
+            &[false, true, true, false],
+            &PkStrategy::ExistingColumn("code".to_string()),
+        );
+        assert!(model.contains("    code = models.CharField(max_length=3, primary_key=True)\n"));
+        assert!(
+            model.contains("    label = models.CharField(max_length=1, null=True, blank=True)\n")
+        );
+        assert!(model.contains("    created_at = models.DateTimeField(null=True, blank=True)\n"));
+        assert!(
+            model.contains("    price = models.DecimalField(max_digits=8, decimal_places=2)\n")
+        );
+    }
+
+    #[test]
+    fn test_generate_django_model_composite_pk_and_empty() {
+        let headers = StringRecord::from(vec!["student_id", "course_id"]);
+        let types = vec![SqlType::Integer, SqlType::Integer];
+        let model = generate_django_model(
+            "enrollment",
+            &headers,
+            &types,
+            &[false, false],
+            &PkStrategy::Composite(vec!["student_id".to_string(), "course_id".to_string()]),
+        );
+        assert!(model.contains(
+            "    pk = models.CompositePrimaryKey(\"student_id\", \"course_id\")\n    \
+             student_id = models.IntegerField()\n"
+        ));
+
+        let empty =
+            generate_django_model("empty", &StringRecord::new(), &[], &[], &PkStrategy::None);
+        assert!(empty.ends_with("class Empty(models.Model):\n    pass\n"));
+    }
+}
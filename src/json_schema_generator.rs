@@ -0,0 +1,118 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{json_string, unique_names, write_to_string};
+
+/// renders the json schema of a single column value.
+fn json_schema_type(sql_type: &SqlType) -> String {
+    match sql_type {
+        SqlType::Integer | SqlType::BigInt => "{\"type\": \"integer\"}".to_string(),
+        SqlType::Float | SqlType::Decimal(..) | SqlType::Percentage => {
+            "{\"type\": \"number\"}".to_string()
+        }
+        SqlType::Boolean => "{\"type\": \"boolean\"}".to_string(),
+        SqlType::Date => "{\"type\": \"string\", \"format\": \"date\"}".to_string(),
+        SqlType::Datetime => "{\"type\": \"string\", \"format\": \"date-time\"}".to_string(),
+        SqlType::Json => "{\"type\": [\"object\", \"array\"]}".to_string(),
+        SqlType::Inet => "{\"type\": \"string\", \"maxLength\": 45}".to_string(),
+        SqlType::Text => "{\"type\": \"string\"}".to_string(),
+        SqlType::Char(len) | SqlType::Varchar(len) => {
+            format!("{{\"type\": \"string\", \"maxLength\": {}}}", len)
+        }
+    }
+}
+
+/// generates a json schema (draft 2020-12) object describing one csv record.
+/// every column becomes a property named after its trimmed header (repeated ones made unique,
+/// `id, id` becomes `id, id_2`); columns whose `nullability` entry is false are listed in
+/// `required`.
+pub fn generate_json_schema(
+    title: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| generate_json_schema_to(writer, title, headers, types, nullability))
+}
+
+/// writes the schema of `generate_json_schema` to `writer`.
+pub fn generate_json_schema_to<W: Write>(
+    writer: &mut W,
+    title: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writer.write_all(b"{\n")?;
+    writer.write_all(b"  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\",\n")?;
+    writeln!(writer, "  \"title\": {},", json_string(title))?;
+    writer.write_all(b"  \"type\": \"object\",\n")?;
+
+    writer.write_all(b"  \"properties\": {\n")?;
+    let names = unique_names(headers.iter().map(|header| header.trim().to_string()));
+    for (i, (name, sql_type)) in names.iter().zip(types.iter()).enumerate() {
+        if i > 0 {
+            writer.write_all(b",\n")?;
+        }
+        write!(
+            writer,
+            "    {}: {}",
+            json_string(name),
+            json_schema_type(sql_type)
+        )?;
+    }
+    writer.write_all(b"\n  },\n")?;
+
+    let required: Vec<String> = names
+        .iter()
+        .zip(nullability.iter())
+        .filter(|&(_, &nullable)| !nullable)
+        .map(|(name, _)| json_string(name))
+        .collect();
+    writeln!(writer, "  \"required\": [{}]", required.join(", "))?;
+
+    writer.write_all(b"}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_json_schema_parses_with_formats() {
+        let headers = StringRecord::from(vec!["id", "born", "updated \"at\"", "score", "name"]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::Date,
+            SqlType::Datetime,
+            SqlType::Float,
+            SqlType::Varchar(20),
+        ];
+        let nullability = vec![false, true, false, true, true];
+        let schema = generate_json_schema("people", &headers, &types, &nullability);
+
+        let json: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(json["title"], "people");
+        assert_eq!(json["properties"]["id"]["type"], "integer");
+        assert_eq!(json["properties"]["born"]["type"], "string");
+        assert_eq!(json["properties"]["born"]["format"], "date");
+        assert_eq!(json["properties"]["updated \"at\""]["format"], "date-time");
+        assert_eq!(json["properties"]["score"]["type"], "number");
+        assert_eq!(json["properties"]["name"]["maxLength"], 20);
+        assert_eq!(
+            json["required"],
+            serde_json::json!(["id", "updated \"at\""])
+        );
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let schema = generate_json_schema("people", &headers, &types, &[false; 3]);
+        assert_eq!(schema.matches("\"id\":").count(), 1);
+        let json: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(json["properties"]["id_2"]["type"], "integer");
+        assert_eq!(json["required"], serde_json::json!(["id", "name", "id_2"]));
+    }
+}
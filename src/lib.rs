@@ -1,57 +1,505 @@
-use std::io::{self, Read};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
 use csv::{ReaderBuilder, StringRecord};
 use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use stats::ColumnStatsCollector;
+use type_inference::ColumnInferenceState;
+use utils::to_snake_case;
 
+mod arrow_generator;
+mod avro_generator;
+mod batch;
+mod diesel_generator;
+mod django_generator;
+mod json_schema_generator;
+mod mapping_generator;
+#[cfg(feature = "json")]
+mod ndjson;
+mod options;
+mod protobuf_generator;
 mod type_inference;
+mod rust_generator;
+mod stats;
 mod sql_generator;
+mod typescript_generator;
+mod validation;
 pub mod python_generator; // declare the new module, make it pub for PkStrategy in main
 mod utils;
 
-pub use type_inference::{infer_sql_type, SqlType};
-pub use sql_generator::generate_sql; // for sql ddl
-pub use python_generator::generate_sqlmodel_python; // for python sqlmodel
+pub use options::{EnabledTypes, InferenceOptions, IntegerPolicy};
+pub use stats::{ColumnStats, SemanticHint, DISTINCT_LIMIT, SAMPLE_SIZE};
+pub use batch::{find_references, generate_sql_batch, topological_order, BatchOrder, TableSchema};
+pub use type_inference::{
+    explain_sql_type, explain_sql_type_with_options, infer_column_flags,
+    infer_column_flags_with_options, infer_nullable, infer_sql_type,
+    infer_sql_type_iter, infer_sql_type_iter_with_options, infer_sql_type_with_options,
+    ColumnFlags, SchemaInferenceState, SqlType,
+};
+pub use sql_generator::{
+    generate_sql, generate_sql_diff, generate_sql_diff_to, generate_sql_to,
+    generate_sql_with_options, generate_sql_with_options_to,
+    enum_check_values, render_column, stats_column_comments, Dialect, SqlFormat, SqlOptions,
+}; // for sql ddl
+pub use python_generator::{
+    generate_dataclass_python, generate_dataclass_python_to, generate_pydantic_python,
+    generate_pydantic_python_to, generate_python, generate_python_to, generate_sqlmodel_python,
+    generate_sqlmodel_python_to, python_type_for, sqlmodel_field_params,
+}; // for python sqlmodel / pydantic / dataclass
+pub use rust_generator::{generate_rust_struct, generate_rust_struct_to}; // for rust serde structs
+pub use typescript_generator::{generate_typescript, generate_typescript_to}; // for typescript interfaces
+pub use json_schema_generator::{generate_json_schema, generate_json_schema_to}; // for json schema documents
+pub use arrow_generator::{generate_arrow_schema, generate_arrow_schema_to}; // for arrow / parquet schemas
+pub use avro_generator::{generate_avro_schema, generate_avro_schema_to}; // for avro record schemas
+pub use protobuf_generator::{generate_protobuf, generate_protobuf_to}; // for protobuf messages
+pub use diesel_generator::{generate_diesel_schema, generate_diesel_schema_to}; // for diesel schema.rs
+pub use django_generator::{generate_django_model, generate_django_model_to}; // for django models
+pub use mapping_generator::{generate_column_mapping, generate_column_mapping_to}; // for machine-readable column lists
+#[cfg(feature = "json")]
+pub use ndjson::{infer_schema_ndjson, infer_schema_ndjson_with_options}; // for json lines input
+pub use validation::{validate_csv, RaggedRecord, ValidationReport}; // for checking well-formedness only
 
 pub fn infer_schema<R: Read>(reader: R) -> io::Result<(StringRecord, Vec<SqlType>)> {
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(reader);
-    let headers = rdr.headers()?.clone();
+    infer_schema_with_options(reader, &InferenceOptions::default())
+}
+
+/// opens the csv file at `path` and infers its schema like `infer_schema`.
+/// the file is read through a buffer, so there is no need to wrap it in a `BufReader` first.
+/// a `.gz` file is decompressed on the fly (see `open_path`).
+pub fn infer_schema_from_path<P: AsRef<Path>>(path: P) -> io::Result<(StringRecord, Vec<SqlType>)> {
+    infer_schema(open_path(path)?)
+}
+
+/// opens the file at `path` for inference. with the `gzip` feature, a file with a `.gz`
+/// extension is decompressed while it is read; without it such a file is an `Unsupported` error.
+pub fn open_path<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn Read + Send>> {
+    let path = path.as_ref();
+    let is_gzip = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gz"));
+    let file = File::open(path)?;
+    match is_gzip {
+        #[cfg(feature = "gzip")]
+        true => Ok(Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(file)))),
+        #[cfg(not(feature = "gzip"))]
+        true => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reading .gz files needs the `gzip` feature",
+        )),
+        false => Ok(Box::new(file)),
+    }
+}
+
+/// infers the schema like `infer_schema`, reading the csv as configured by `options`.
+pub fn infer_schema_with_options<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, Vec<SqlType>)> {
+    let (headers, inferred_types, _) = infer_schema_with_nullability(reader, options)?;
+    Ok((headers, inferred_types))
+}
+
+/// infers the schema like `infer_schema_with_options`, additionally reporting for each column
+/// whether it contained empty values (see `infer_nullable`).
+///
+/// with `options.sample_rows`, reading stops after that many records, unless
+/// `options.verify_tail` checks the rest against the sampled types.
+pub fn infer_schema_with_nullability<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, Vec<SqlType>, Vec<bool>)> {
+    let schema = infer_schema_summary(reader, options)?;
+    Ok((schema.headers, schema.types, schema.nullability))
+}
+
+/// a schema as returned by `infer_schema_summary`: the columns together with the number of
+/// records they were inferred from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredSchema {
+    pub headers: StringRecord,
+    pub types: Vec<SqlType>,
+    pub nullability: Vec<bool>,
+    /// the number of data records read (up to `options.sample_rows`, unless `verify_tail` read
+    /// them all). 0 means the input had a
+    /// header but no data rows, so the `Varchar(0)` of every column is a default without any
+    /// evidence; with records, a `Varchar(0)` column was empty in every one of them.
+    /// blank lines are skipped by the csv reader and do not count as records.
+    pub record_count: usize,
+}
+
+impl InferredSchema {
+    /// returns true if the types were inferred from at least one data record.
+    pub fn has_data_rows(&self) -> bool {
+        self.record_count > 0
+    }
+}
+
+/// infers the schema like `infer_schema_with_nullability`, also reporting how many records
+/// were read, which tells a header-only file apart from one whose values are all empty.
+pub fn infer_schema_summary<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<InferredSchema> {
+    infer_schema_summary_with_progress(reader, options, |_| {})
+}
+
+/// infers the schema like `infer_schema_with_options`, calling `progress` with the number of
+/// records read so far after every `CHUNK_SIZE` records, and once more with the final count.
+/// this lets a ui show progress during long inferences; the callback runs on the calling thread.
+pub fn infer_schema_with_progress<R, P>(
+    reader: R,
+    options: &InferenceOptions,
+    progress: P,
+) -> io::Result<(StringRecord, Vec<SqlType>)>
+where
+    R: Read,
+    P: FnMut(u64),
+{
+    let schema = infer_schema_summary_with_progress(reader, options, progress)?;
+    Ok((schema.headers, schema.types))
+}
+
+fn infer_schema_summary_with_progress<R, P>(
+    reader: R,
+    options: &InferenceOptions,
+    progress: P,
+) -> io::Result<InferredSchema>
+where
+    R: Read,
+    P: FnMut(u64),
+{
+    let (headers, states) = fold_columns(
+        reader,
+        options,
+        ColumnInferenceState::new(options),
+        ColumnInferenceState::observe,
+        ColumnInferenceState::start_verifying,
+        progress,
+    )?;
+
+    let inferred_types: Vec<SqlType> = headers
+        .iter()
+        .zip(&states)
+        .map(|(header, state)| options.resolve_type(header, state.finalize(), state.max_len()))
+        .collect();
+    for ((header, sql_type), state) in headers.iter().zip(&inferred_types).zip(&states) {
+        log::info!("column \"{}\": {:?} ({} empty values)", header.trim(), sql_type, state.empty_count());
+    }
+    let nullability = states.iter().map(ColumnInferenceState::is_nullable).collect();
+    // every record contributes one value (possibly empty) to each column.
+    let record_count = states.first().map_or(0, ColumnInferenceState::value_count);
+
+    Ok(InferredSchema {
+        headers,
+        types: inferred_types,
+        nullability,
+        record_count,
+    })
+}
+
+/// infers the schema like `infer_schema_with_options`, reporting a `ColumnStats` profile
+/// (null count, distinct count, lengths and sample values) for each column.
+pub fn infer_schema_with_stats<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, Vec<ColumnStats>)> {
+    let (headers, collectors) =
+        fold_columns(
+            reader,
+            options,
+            ColumnStatsCollector::new(options),
+            ColumnStatsCollector::observe,
+            ColumnStatsCollector::start_verifying,
+            |_| {},
+        )?;
+    let stats = headers
+        .iter()
+        .zip(collectors)
+        .map(|(header, collector)| collector.finish(header))
+        .collect();
+    Ok((headers, stats))
+}
+
+/// reads the csv and folds every column's values into its own copy of `initial`.
+///
+/// records are streamed: they are read in chunks of `CHUNK_SIZE` and folded column by column
+/// (in parallel across columns, see `options.threads`), so memory use does not grow with the
+/// number of rows in the file. null tokens are passed to `observe` as empty strings.
+/// with `options.verify_tail`, `start_verifying` is called on every state once the sample is
+/// read, and the remaining records are folded in after it. with `options.sample_from_tail`,
+/// only the last `sample_rows` records are held (in a ring buffer) and folded at the end.
+/// `progress` receives the running record count after every chunk and the total at the end.
+fn fold_columns<R, S, F, V, P>(
+    reader: R,
+    options: &InferenceOptions,
+    initial: S,
+    observe: F,
+    start_verifying: V,
+    mut progress: P,
+) -> io::Result<(StringRecord, Vec<S>)>
+where
+    R: Read,
+    S: Clone + Send,
+    F: Fn(&mut S, &str) + Sync,
+    V: Fn(&mut S),
+    P: FnMut(u64),
+{
+    let (headers, mut rdr) = open_csv(reader, options)?;
+
+    let pool = match options.threads {
+        Some(threads) if threads > 1 => Some(
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(io::Error::other)?,
+        ),
+        _ => None,
+    };
+
+    let mut states = vec![initial; headers.len()];
+    let fold_records = |states: &mut Vec<S>, records: &[StringRecord]| {
+        let observe_column = |(i, state): (usize, &mut S)| {
+            for record in records {
+                observe(state, column_value(record, i, options));
+            }
+        };
+        match (&pool, options.threads) {
+            (Some(pool), _) => pool.install(|| states.par_iter_mut().enumerate().for_each(observe_column)),
+            (None, Some(1)) => states.iter_mut().enumerate().for_each(observe_column),
+            (None, _) => states.par_iter_mut().enumerate().for_each(observe_column),
+        }
+    };
+
+    if options.sample_from_tail
+        && let Some(sample_rows) = options.sample_rows
+    {
+        let mut tail: VecDeque<StringRecord> = VecDeque::with_capacity(sample_rows.min(CHUNK_SIZE));
+        let mut record = StringRecord::new();
+        let mut records_read: u64 = 0;
+        while rdr.read_record(&mut record).map_err(map_csv_error)? {
+            records_read += 1;
+            if tail.len() < sample_rows {
+                tail.push_back(std::mem::take(&mut record));
+            } else if let Some(oldest) = tail.pop_front() {
+                // the dropped record's buffers are reused for the next read.
+                tail.push_back(std::mem::replace(&mut record, oldest));
+            }
+            if records_read.is_multiple_of(CHUNK_SIZE as u64) {
+                progress(records_read);
+            }
+        }
+        fold_records(&mut states, tail.make_contiguous());
+        progress(records_read);
+        log::info!(
+            "read {} records with {} columns, inferred from the last {}",
+            records_read,
+            headers.len(),
+            tail.len()
+        );
+        if records_read == 0 {
+            log::warn!("the csv has a header row but no data rows");
+        }
+        return Ok((headers, states));
+    }
+
+    // the chunk's records are reused between reads to avoid reallocating their buffers.
+    let mut chunk = vec![StringRecord::new(); CHUNK_SIZE];
+    let mut remaining = options.sample_rows.unwrap_or(usize::MAX);
+    let mut records_read = 0;
+    loop {
+        let chunk_len = CHUNK_SIZE.min(remaining);
+        let mut filled = 0;
+        while filled < chunk_len && rdr.read_record(&mut chunk[filled]).map_err(map_csv_error)? {
+            filled += 1;
+        }
+        remaining -= filled;
+        records_read += filled;
+
+        fold_records(&mut states, &chunk[..filled]);
+
+        if filled < chunk_len {
+            break;
+        }
+        if remaining == 0 {
+            if !options.verify_tail {
+                break;
+            }
+            log::debug!("verifying the sampled types against the remaining records");
+            states.iter_mut().for_each(&start_verifying);
+            remaining = usize::MAX;
+        }
+        log::debug!("read {} records so far", records_read);
+        progress(records_read as u64);
+    }
+    progress(records_read as u64);
+    log::info!("read {} records with {} columns", records_read, headers.len());
+    if records_read == 0 {
+        // every column then infers as an empty varchar, which is rarely what was intended.
+        log::warn!("the csv has a header row but no data rows");
+    }
+
+    Ok((headers, states))
+}
+
+/// reads the csv and explains, per column, how its type was inferred (see `explain_sql_type`).
+/// records are streamed like for inference, honoring `sample_rows`, `verify_tail` and
+/// `sample_from_tail`.
+pub fn explain_schema<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, Vec<String>)> {
+    let (headers, states) = fold_columns(
+        reader,
+        options,
+        ColumnInferenceState::new(options),
+        ColumnInferenceState::observe,
+        ColumnInferenceState::start_verifying,
+        |_| {},
+    )?;
+    let explanations = states.iter().map(ColumnInferenceState::explain).collect();
+    Ok((headers, explanations))
+}
+
+/// moves the columns named in `first` (e.g. the primary key) to the front of the schema, in the
+/// order given, keeping the csv order of all other columns. names are compared like the
+/// python field names (`User ID` matches `user_id`); names matching no header are ignored.
+/// the generators emit columns in schema order, so this is how a key is hoisted to the top.
+pub fn hoist_columns(
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    first: &[String],
+) -> (StringRecord, Vec<SqlType>, Vec<bool>) {
+    let mut order: Vec<usize> = Vec::with_capacity(headers.len());
+    for name in first {
+        if let Some(i) = headers.iter().position(|header| to_snake_case(header) == to_snake_case(name))
+            && !order.contains(&i)
+        {
+            order.push(i);
+        }
+    }
+    let rest: Vec<usize> = (0..headers.len()).filter(|i| !order.contains(i)).collect();
+    order.extend(rest);
+
+    (
+        order.iter().map(|&i| &headers[i]).collect(),
+        order.iter().map(|&i| types[i].clone()).collect(),
+        order.iter().map(|&i| nullability[i]).collect(),
+    )
+}
+
+/// the number of records read at a time by the streaming inference.
+const CHUNK_SIZE: usize = 8192;
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// returns the value of column `i`, or an empty string if it is a configured null token or,
+/// in flexible mode, missing from a short record.
+fn column_value<'a>(record: &'a StringRecord, i: usize, options: &InferenceOptions) -> &'a str {
+    let value = record.get(i).unwrap_or("");
+    if options.is_null_token(value) { "" } else { value }
+}
+
+/// opens a csv reader configured by `options` and reads the headers.
+/// the returned reader is positioned at the first data record.
+fn open_csv<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, csv::Reader<BufReader<R>>)> {
+    let mut reader = BufReader::new(reader);
+
+    // files exported from excel start with a utf-8 bom. the csv reader strips it from the
+    // first record itself, but a bom in front of a banner or comment line would hide the
+    // comment marker, so it is removed before anything else looks at the input.
+    if reader.fill_buf()?.starts_with(UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+
+    // leading banner lines carry no marker and often a different field count than the
+    // header, so they are dropped as raw lines before the csv reader ever sees them.
+    let mut skipped_line = Vec::new();
+    for _ in 0..options.skip_rows {
+        skipped_line.clear();
+        if reader.read_until(b'\n', &mut skipped_line)? == 0 {
+            break;
+        }
+    }
+
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(options.has_headers)
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .escape(options.escape)
+        .comment(options.comment)
+        .flexible(options.flexible)
+        .from_reader(reader);
+    // without a header row, csv::Reader::headers() returns the first record (which is
+    // still yielded by records()), so only its field count is used to name the columns.
+    let headers = if rdr.has_headers() {
+        rdr.headers().map_err(map_csv_error)?.clone()
+    } else {
+        let field_count = rdr.headers().map_err(map_csv_error)?.len();
+        if options.column_names.is_empty() {
+            (1..=field_count).map(|i| format!("col_{}", i)).collect()
+        } else if options.column_names.len() == field_count {
+            StringRecord::from(options.column_names.clone())
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} column names given, but the csv has {} fields",
+                    options.column_names.len(),
+                    field_count
+                ),
+            ));
+        }
+    };
 
-    // if headers were expected but are empty (0 fields),
-    // this implies an empty or malformed csv input that csv::Reader::headers()
-    // should have errored on for completely empty input.
+    // if headers are empty (0 fields), this implies an empty or malformed csv input
+    // that csv::Reader::headers() should have errored on for completely empty input.
     // this check makes the function robust if headers() unexpectedly returns ok with 0 fields.
-    if rdr.has_headers() && headers.is_empty() {
+    if headers.is_empty() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             "csv input is empty or headers are malformed (0 fields)",
         ));
     }
-    // attempt to collect all records. if a csv::error occurs, handle it.
-    let records = match rdr.records().collect::<Result<Vec<_>, csv::Error>>() {
-        Ok(recs) => recs,
-        Err(csv_err) => {
-            // if the csv error is specifically for unequal record lengths,
-            // we ensure it's mapped to io::errorkind::invaliddata.
-            // the csv crate (version 1.3.1) should ideally handle this mapping correctly
-            // via its `from<csv::error> for io::error` implementation.
-            // this explicit check provides a safeguard or override if the observed behavior differs.
-            if matches!(csv_err.kind(), csv::ErrorKind::UnequalLengths { .. }) {
-                return Err(io::Error::new(io::ErrorKind::InvalidData, csv_err));
-            } else {
-                return Err(io::Error::from(csv_err)); // use default conversion for other csv errors
-            }
-        }
-    };
 
-    let num_columns = headers.len();
-    let inferred_types = (0..num_columns)
-        .into_par_iter()
-        .map(|i| {
-            let column_data: Vec<&str> = records.iter().map(|record| &record[i]).collect();
-            infer_sql_type(&column_data)
-        })
-        .collect();
+    Ok((headers, rdr))
+}
 
-    Ok((headers, inferred_types))
+/// maps an error from reading records to `io::Error`.
+/// a record with the wrong number of fields becomes `InvalidData`, with a message naming the
+/// record (the header row is record 0), its line and the expected vs actual field count.
+/// a value that is not valid utf-8 (e.g. a latin-1 encoded file) also becomes `InvalidData`,
+/// naming the field and the byte offset of its first invalid byte.
+fn map_csv_error(csv_err: csv::Error) -> io::Error {
+    let location = |pos: &Option<csv::Position>| match pos {
+        Some(pos) => format!("record {} (line {})", pos.record(), pos.line()),
+        None => "a record".to_string(),
+    };
+    match csv_err.kind() {
+        csv::ErrorKind::UnequalLengths { pos, expected_len, len } => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} has {} fields, expected {}", location(pos), len, expected_len),
+        ),
+        // the csv crate reports where the record starts, and where in the field the invalid
+        // byte is, but not where the field starts.
+        csv::ErrorKind::Utf8 { pos, err } => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "invalid utf-8 at byte {} of field {} in {}{}; the file is probably in another \
+                 encoding (e.g. latin-1) and has to be converted to utf-8 first",
+                err.valid_up_to(),
+                err.field() + 1,
+                location(pos),
+                pos.as_ref()
+                    .map(|pos| format!(", which starts at byte {}", pos.byte()))
+                    .unwrap_or_default()
+            ),
+        ),
+        _ => io::Error::from(csv_err), // use default conversion for other csv errors
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +577,469 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stats_quoted_two_line_cell() {
+        // both notes are 5 bytes long, one of them across two lines.
+        let csv_data = "id,note\n1,\"ab\ncd\"\n2,efghi\n";
+        let (_, stats) = infer_schema_with_stats(Cursor::new(csv_data), &InferenceOptions::default()).unwrap();
+
+        assert_eq!(stats[0].row_count, 2);
+        assert_eq!(stats[1].sql_type, SqlType::Varchar(5));
+        assert_eq!((stats[1].min_len, stats[1].max_len), (5, 5));
+        assert_eq!(stats[1].sample_values, vec!["ab\ncd".to_string(), "efghi".to_string()]);
+    }
+
+    #[test]
+    fn test_infer_schema_quoted_multiline_field() {
+        // the quoted note spans two lines; the csv reader keeps the embedded newline,
+        // so the column length counts it and the row count is unaffected.
+        let csv_data = "id,note\n1,\"first line\nsecond\"\n2,\"2023-01-01\"\n3,plain";
+        let reader = Cursor::new(csv_data);
+        let (headers, types) = infer_schema(reader).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["id", "note"]));
+        assert_eq!(
+            types,
+            vec![
+                SqlType::Integer,     // 1, 2, 3
+                SqlType::Varchar(17)  // "first line\nsecond" including the newline
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer_schema_from_path() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "id,name\n1,Alice\n2,Bob").unwrap();
+
+        let (headers, types) = infer_schema_from_path(file.path()).unwrap();
+        assert_eq!(headers, StringRecord::from(vec!["id", "name"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Varchar(5)]);
+
+        let missing = file.path().with_extension("missing");
+        assert_eq!(
+            infer_schema_from_path(missing).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_infer_schema_from_gzip() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"id,name\n1,Alice\n2,Bob").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let (headers, types) = infer_schema(flate2::read::GzDecoder::new(Cursor::new(&compressed))).unwrap();
+        assert_eq!(headers, StringRecord::from(vec!["id", "name"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Varchar(5)]);
+
+        let mut file = tempfile::Builder::new().suffix(".csv.gz").tempfile().unwrap();
+        file.write_all(&compressed).unwrap();
+        let (_, types_from_path) = infer_schema_from_path(file.path()).unwrap();
+        assert_eq!(types_from_path, types);
+    }
+
+    #[test]
+    fn test_infer_schema_with_nullability() {
+        let csv_data = "name,age\nAlice,\nBob,24";
+        let reader = Cursor::new(csv_data);
+        let (headers, types, nullability) = infer_schema_with_nullability(reader, &InferenceOptions::default()).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "age"]));
+        assert_eq!(types, vec![SqlType::Varchar(5), SqlType::Integer]);
+        assert_eq!(nullability, vec![false, true]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_sample_rows() {
+        let mut csv_data = String::from("id,amount\n");
+        for i in 1..100 {
+            csv_data.push_str(&format!("{},{}\n", i, i * 10));
+        }
+        csv_data.push_str("100,12.5\n"); // row 100 would make the column a float
+        let options = InferenceOptions {
+            sample_rows: Some(2),
+            ..InferenceOptions::default()
+        };
+
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Integer]);
+
+        let (_, types) = infer_schema(Cursor::new(csv_data)).unwrap();
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Float]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_char_and_varchar_columns() {
+        let csv_data = "country,currency,city\nFR,EUR,Paris\nNO,NOK,Oslo\n";
+        let options = InferenceOptions::default().with_varchar_column("country");
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Varchar(2), SqlType::Char(3), SqlType::Varchar(5)]);
+
+        let options = InferenceOptions::default()
+            .with_prefer_varchar_over_char(true)
+            .with_char_column("currency")
+            .with_char_column("city");
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Varchar(2), SqlType::Char(3), SqlType::Char(5)]);
+    }
+
+    #[test]
+    fn test_infer_schema_default_type_for_empty() {
+        let csv_data = "id,retries,note\n2,,\n3,,\n";
+        let options = InferenceOptions::default()
+            .with_default_type_for_empty(Some(SqlType::Integer))
+            .with_type_override("note", SqlType::Text);
+        let (_, types, nullability) =
+            infer_schema_with_nullability(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Integer, SqlType::Text]);
+        assert_eq!(nullability, vec![false, true, true]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_verify_tail() {
+        let mut csv_data = String::from("id,amount,code\n");
+        for i in 1..1000 {
+            csv_data.push_str(&format!("{},{},A{}\n", i, i * 10, i % 10));
+        }
+        csv_data.push_str("1000,12.5,ZZZ\n"); // row 1000 violates the sampled types
+        let options = InferenceOptions::default().with_sample_rows(Some(100));
+
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Integer, SqlType::Char(2)]);
+
+        let options = options.with_verify_tail(true);
+        let schema = infer_schema_summary(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(schema.types, vec![SqlType::Integer, SqlType::Float, SqlType::Varchar(3)]);
+        assert_eq!(schema.record_count, 1000);
+        let (_, stats) = infer_schema_with_stats(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(stats[1].sql_type, SqlType::Float);
+        assert_eq!(stats[1].row_count, 1000);
+    }
+
+    #[test]
+    fn test_infer_schema_sample_from_tail() {
+        let mut csv_data = String::from("id,amount\n");
+        for i in 2..100 {
+            csv_data.push_str(&format!("{},{}\n", i, i * 10));
+        }
+        for i in 100..120 {
+            csv_data.push_str(&format!("{},{}.5\n", i, i));
+        }
+        let options = InferenceOptions::default().with_sample_rows(Some(10));
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(types[1], SqlType::Integer);
+
+        let options = options.with_sample_from_tail(true);
+        let schema = infer_schema_summary(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(schema.types, vec![SqlType::Integer, SqlType::Float]);
+        assert_eq!(schema.record_count, 10);
+        let (_, stats) = infer_schema_with_stats(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!((stats[1].sql_type.clone(), stats[1].row_count), (SqlType::Float, 10));
+        assert_eq!(stats[1].sample_values.first().map(String::as_str), Some("110.5"));
+        let (_, explanations) = explain_schema(Cursor::new(csv_data), &options).unwrap();
+        assert!(explanations[1].ends_with("result: Float"));
+    }
+
+    #[test]
+    fn test_infer_schema_verify_tail_demotes_sampled_boolean() {
+        let mut csv_data = String::from("id,status,flag\n");
+        for i in 2..200 {
+            csv_data.push_str(&format!("{},{},{}\n", i, i % 2, if i % 2 == 0 { "yes" } else { "no" }));
+        }
+        csv_data.push_str("200,2,2\n"); // a status of 2 only appears after the sample
+        let options = InferenceOptions::default()
+            .with_sample_rows(Some(50))
+            .with_boolean_values(["1", "yes"], ["0", "no"]);
+
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data.clone()), &options).unwrap();
+        assert_eq!(types[1], SqlType::Boolean);
+        assert_eq!(types[2], SqlType::Boolean);
+
+        let options = options.with_verify_tail(true);
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types[1], SqlType::Integer);
+        // a boolean sampled from words still falls back to a varchar.
+        assert_eq!(types[2], SqlType::Varchar(3));
+    }
+
+    #[test]
+    fn test_infer_schema_sequential_matches_parallel() {
+        let csv_data = "id,name,score,joined,active,code\n\
+                        1,Alice,1.5,2023-01-01,true,AB\n\
+                        2,Bob,,2023-02-01,false,CD\n\
+                        3000000000,Charlie,7,2023-03-01 10:00:00,,EF";
+
+        let results: Vec<_> = [None, Some(1), Some(3)]
+            .into_iter()
+            .map(|threads| {
+                let options = InferenceOptions { threads, ..InferenceOptions::default() };
+                infer_schema_with_nullability(Cursor::new(csv_data), &options).unwrap()
+            })
+            .collect();
+
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+        assert_eq!(
+            results[0].1,
+            vec![
+                SqlType::BigInt,
+                SqlType::Varchar(7),
+                SqlType::Float,
+                SqlType::Varchar(19),
+                SqlType::Boolean,
+                SqlType::Char(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer_schema_across_chunk_boundary() {
+        // the only float and the only empty value sit in the second chunk.
+        let mut csv_data = String::from("id,value\n");
+        for i in 0..CHUNK_SIZE {
+            csv_data.push_str(&format!("{},{}\n", i, i));
+        }
+        csv_data.push_str("x,2.5\n,\n");
+        let reader = Cursor::new(csv_data);
+        let (_, types, nullability) = infer_schema_with_nullability(reader, &InferenceOptions::default()).unwrap();
+
+        assert_eq!(types, vec![SqlType::Varchar(4), SqlType::Float]);
+        assert_eq!(nullability, vec![true, true]);
+    }
+
+    #[test]
+    fn test_explain_schema() {
+        let csv_data = "id,answer\n1,yes\n2,maybe";
+        let reader = Cursor::new(csv_data);
+        let (headers, explanations) = explain_schema(reader, &InferenceOptions::default()).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["id", "answer"]));
+        assert_eq!(explanations.len(), 2);
+        assert!(explanations[0].contains("boolean: no (value '2')"));
+        assert!(explanations[0].ends_with("result: Integer"));
+        assert!(explanations[1].contains("integer: no (value 'yes')"));
+        assert!(explanations[1].ends_with("result: Varchar(5)"));
+    }
+
+    #[test]
+    fn test_explain_schema_verify_tail() {
+        let mut csv_data = String::from("amount,code\n");
+        for i in 2..200 {
+            csv_data.push_str(&format!("{},AB\n", i));
+        }
+        csv_data.push_str("12.5,ABC\n");
+        let options = InferenceOptions::default()
+            .with_sample_rows(Some(50))
+            .with_verify_tail(true);
+        let (_, explanations) = explain_schema(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(
+            explanations[0],
+            "boolean: no (value '2')\ninteger: no (value '12.5')\nbigint: no (value '12.5')\n\
+             float: yes\nresult: Float"
+        );
+        // the tail value widens the sampled char.
+        assert!(explanations[1].contains("char: no (value 'ABC' has length 3, expected 2)"));
+        assert!(explanations[1].ends_with("result: Varchar(3)"));
+    }
+
+    #[test]
+    fn test_infer_schema_with_semicolon_delimiter() {
+        let csv_data = "a;b\n1;2";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            delimiter: b';',
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["a", "b"]));
+        assert_eq!(types, vec![SqlType::Boolean, SqlType::Integer]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_tab_delimiter() {
+        let csv_data = "name\tcity\nAlice\tNew York, NY";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            delimiter: b'\t',
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "city"]));
+        assert_eq!(types, vec![SqlType::Char(5), SqlType::Char(12)]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_single_quote_and_backslash_escape() {
+        // the quoted name contains the delimiter and an escaped quote.
+        let csv_data = "name,city\n'Smith, J',Paris\n'O\\'Brien, K',Rome";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            quote: b'\'',
+            escape: Some(b'\\'),
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "city"]));
+        assert_eq!(types, vec![SqlType::Varchar(10), SqlType::Varchar(5)]); // o'brien, k
+    }
+
+    #[test]
+    fn test_infer_schema_skips_comment_lines() {
+        let csv_data = "# note: exported 2024-01-01\n# source: crm\nname,age\nAlice,30\n# trailing note\nBob,24";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            comment: Some(b'#'),
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "age"]));
+        assert_eq!(types, vec![SqlType::Varchar(5), SqlType::Integer]);
+    }
+
+    #[test]
+    fn test_infer_schema_without_header_row() {
+        let csv_data = "1,Alice\n2,Bob";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            has_headers: false,
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["col_1", "col_2"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Varchar(5)]);
+    }
+
+    #[test]
+    fn test_infer_schema_without_header_row_named_columns() {
+        let options = InferenceOptions::default()
+            .with_headers(false)
+            .with_column_names(["id", "name"]);
+        let (headers, types) = infer_schema_with_options(Cursor::new("1,Alice\n2,Bob"), &options).unwrap();
+        assert_eq!(headers, StringRecord::from(vec!["id", "name"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Varchar(5)]);
+
+        let options = options.with_column_names(["id"]);
+        let err = infer_schema_with_options(Cursor::new("1,Alice\n2,Bob"), &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "1 column names given, but the csv has 2 fields");
+    }
+
+    #[test]
+    fn test_infer_schema_skips_leading_rows() {
+        let csv_data = "Quarterly Export\nname,age\nAlice,30\nBob,24";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            skip_rows: 1,
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "age"]));
+        assert_eq!(types, vec![SqlType::Varchar(5), SqlType::Integer]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_null_tokens() {
+        let csv_data = "name,age,note\nAlice,NA,n/a\nBob,30,NULL\nCarol,null,N/A";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            null_tokens: vec!["NA".to_string(), "N/A".to_string(), "NULL".to_string()],
+            ..Default::default()
+        };
+        let (headers, types, nullability) =
+            infer_schema_with_nullability(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "age", "note"]));
+        assert_eq!(
+            types,
+            vec![
+                SqlType::Varchar(5), // carol
+                SqlType::Integer,    // NA, 30, null -> integer
+                SqlType::Varchar(0)  // only null tokens, like an all-empty column
+            ]
+        );
+        assert_eq!(nullability, vec![false, true, true]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_stats() {
+        let csv_data = "id,city\n1,Paris\n2,NA\n3,Oslo\n4,Paris";
+        let options = InferenceOptions::default().with_null_tokens(["NA"]);
+        let (headers, stats) = infer_schema_with_stats(Cursor::new(csv_data), &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["id", "city"]));
+        assert_eq!(stats[0].sql_type, SqlType::Integer);
+        assert_eq!(stats[0].null_count, 0);
+        assert_eq!(stats[0].distinct_count, Some(4));
+        assert_eq!(stats[1].sql_type, SqlType::Varchar(5));
+        assert_eq!(stats[1].null_count, 1);
+        assert_eq!(stats[1].distinct_count, Some(2));
+        assert_eq!((stats[1].min_len, stats[1].max_len), (4, 5));
+        assert_eq!(stats[1].sample_values, vec!["Paris", "Oslo"]);
+        assert_eq!(stats[0].row_count, 4);
+    }
+
+    #[test]
+    fn test_infer_schema_with_stats_headers_only() {
+        let options = InferenceOptions::default();
+        let (headers, stats) = infer_schema_with_stats(Cursor::new("id,name\n"), &options).unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert!(stats.iter().all(|column| column.row_count == 0));
+        assert_eq!(stats[0].sql_type, SqlType::Varchar(0));
+    }
+
+    #[test]
+    fn test_infer_schema_with_builder_options() {
+        let csv_data = "id;score\n1;NA\n2;3.5";
+        let options = InferenceOptions::default()
+            .with_delimiter(b';')
+            .with_null_tokens(["na"]);
+        let (headers, types, nullability) = infer_schema_with_nullability(Cursor::new(csv_data), &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["id", "score"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Float]);
+        assert_eq!(nullability, vec![false, true]);
+    }
+
+    #[test]
+    fn test_infer_schema_strips_utf8_bom() {
+        let csv_data = "\u{feff}name,age\nAlice,30";
+        let reader = Cursor::new(csv_data);
+        let (headers, types) = infer_schema(reader).unwrap();
+
+        assert_eq!(&headers[0], "name");
+        assert_eq!(headers, StringRecord::from(vec!["name", "age"]));
+        assert_eq!(types, vec![SqlType::Char(5), SqlType::Integer]);
+    }
+
+    #[test]
+    fn test_infer_schema_bom_before_comment_line() {
+        let csv_data = "\u{feff}# exported from excel\nname,age\n\u{feff}Alice,30";
+        let reader = Cursor::new(csv_data);
+        let options = InferenceOptions {
+            comment: Some(b'#'),
+            ..Default::default()
+        };
+        let (headers, types) = infer_schema_with_options(reader, &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["name", "age"]));
+        // only the very start of the input is touched, a bom inside a value is kept.
+        assert_eq!(types, vec![SqlType::Char(8), SqlType::Integer]);
+    }
+
     #[test]
     fn test_infer_schema_only_headers() {
         let csv_data = "col1,col2,col3\n";
@@ -146,6 +1057,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_infer_schema_summary_header_only_vs_empty_values() {
+        let options = InferenceOptions::default();
+        let header_only = infer_schema_summary(Cursor::new("col1\n"), &options).unwrap();
+        assert_eq!(header_only.types, vec![SqlType::Varchar(0)]);
+        assert_eq!(header_only.record_count, 0);
+        assert!(!header_only.has_data_rows());
+
+        // blank lines are not records, so this is still a header-only file.
+        let blank_lines = infer_schema_summary(Cursor::new("col1\n\n\n"), &options).unwrap();
+        assert!(!blank_lines.has_data_rows());
+
+        let empty_values = infer_schema_summary(Cursor::new("col1,col2\n,\n,\n"), &options).unwrap();
+        assert_eq!(empty_values.types, vec![SqlType::Varchar(0), SqlType::Varchar(0)]);
+        assert_eq!(empty_values.nullability, vec![true, true]);
+        assert_eq!(empty_values.record_count, 2);
+        assert!(empty_values.has_data_rows());
+    }
+
+    #[test]
+    fn test_infer_schema_with_progress() {
+        let num_rows = CHUNK_SIZE * 2 + 100;
+        let mut csv_data = String::from("id,name\n");
+        for i in 0..num_rows {
+            csv_data.push_str(&format!("{},name{}\n", i, i));
+        }
+
+        let mut reported = Vec::new();
+        let (headers, types) = infer_schema_with_progress(
+            Cursor::new(csv_data),
+            &InferenceOptions::default(),
+            |count| reported.push(count),
+        )
+        .unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(types[0], SqlType::Integer);
+        assert_eq!(
+            reported,
+            vec![CHUNK_SIZE as u64, 2 * CHUNK_SIZE as u64, num_rows as u64]
+        );
+    }
+
     #[test]
     fn test_infer_schema_empty_input() {
         let csv_data = "";
@@ -167,6 +1120,113 @@ mod tests {
         assert!(result.is_err()); // expecting an error from the csv parsing
     }
 
+    #[test]
+    fn test_infer_schema_flexible_tolerates_ragged_rows() {
+        let csv_data = "a,b,c\n1,2\n4,5,6\n7,8,9,10";
+        let options = InferenceOptions::default().with_flexible(true);
+        let (headers, types, nullability) = infer_schema_with_nullability(Cursor::new(csv_data), &options).unwrap();
+
+        assert_eq!(headers, StringRecord::from(vec!["a", "b", "c"]));
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Integer, SqlType::Integer]);
+        assert_eq!(nullability, vec![false, false, true]); // the short row has no `c`
+
+        assert!(infer_schema(Cursor::new(csv_data)).is_err()); // strict by default
+    }
+
+    #[test]
+    fn test_infer_schema_with_thousands_separator() {
+        let csv_data = "id,amount\n1,\"1,234\"\n2,\"12,500\"";
+        let options = InferenceOptions::default().with_thousands_separator(Some(','));
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Integer, SqlType::Integer]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_decimal_comma() {
+        let csv_data = "pi;two\n\"3,14\";\"2,0\"";
+        let options = InferenceOptions::default()
+            .with_delimiter(b';')
+            .with_decimal_separator(',');
+        let (_, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Float, SqlType::Float]);
+    }
+
+    #[test]
+    fn test_infer_schema_with_type_overrides() {
+        let csv_data = "zip, code ,score\n01234,7,1\n98765,12,2";
+        let options = InferenceOptions::default()
+            .with_type_override("zip", SqlType::Varchar(0))
+            .with_type_override("code", SqlType::Char(2))
+            .with_type_override("missing", SqlType::Date);
+        let (headers, types) = infer_schema_with_options(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(types, vec![SqlType::Varchar(5), SqlType::Char(2), SqlType::Integer]);
+
+        let (_, stats) = infer_schema_with_stats(Cursor::new(csv_data), &options).unwrap();
+        assert_eq!(stats[0].sql_type, SqlType::Varchar(5));
+
+        // the generators only see the resulting types
+        assert!(generate_sql("codes", &headers, &types).contains("  \"zip\" VARCHAR(5),\n"));
+        let python = generate_sqlmodel_python(
+            "codes",
+            &headers,
+            &types,
+            &[false, false, false],
+            &python_generator::PkStrategy::None,
+            python_generator::PythonTarget::default(),
+        );
+        assert!(python.contains("    zip: str = Field(max_length=5)\n"));
+    }
+
+    #[test]
+    fn test_hoist_columns_moves_the_key_first() {
+        let headers = StringRecord::from(vec!["name", "age", "user_id"]);
+        let types = vec![SqlType::Varchar(10), SqlType::Integer, SqlType::BigInt];
+        let nullability = vec![true, true, false];
+
+        let (headers, types, nullability) = hoist_columns(&headers, &types, &nullability, &["user_id".to_string()]);
+        assert_eq!(headers, StringRecord::from(vec!["user_id", "name", "age"]));
+        assert_eq!(types, vec![SqlType::BigInt, SqlType::Varchar(10), SqlType::Integer]);
+        assert_eq!(nullability, vec![false, true, true]);
+
+        let python = generate_sqlmodel_python(
+            "users",
+            &headers,
+            &types,
+            &nullability,
+            &python_generator::PkStrategy::ExistingColumn("user_id".to_string()),
+            python_generator::PythonTarget::default(),
+        );
+        assert!(python.contains("table=True):\n    user_id: int | None = Field(default=None, primary_key=True)\n    name:"));
+        assert!(generate_sql("users", &headers, &types).starts_with("CREATE TABLE \"users\" (\n  \"user_id\" BIGINT,\n"));
+    }
+
+    #[test]
+    fn test_infer_schema_unequal_lengths_names_the_record() {
+        let csv_data = "a,b,c\n1,2,3\n4,5,6\n7,8\n9,10,11";
+        let err = infer_schema(Cursor::new(csv_data)).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "record 3 (line 4) has 2 fields, expected 3");
+    }
+
+    #[test]
+    fn test_infer_schema_invalid_utf8_names_the_offset() {
+        // "café" in latin-1: 0xe9 is not valid utf-8.
+        let csv_data: &[u8] = b"id,name\n1,Bob\n2,caf\xe9\n";
+        let err = infer_schema(Cursor::new(csv_data)).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "invalid utf-8 at byte 3 of field 2 in record 2 (line 3), which starts at byte 14; \
+             the file is probably in another encoding (e.g. latin-1) and has to be converted to \
+             utf-8 first"
+        );
+
+        let err = infer_schema(Cursor::new(&b"id,n\xe4me\n1,2\n"[..])).unwrap_err();
+        assert!(err.to_string().starts_with("invalid utf-8 at byte 1 of field 2 in record 0 (line 1)"));
+    }
+
     #[test]
     fn test_infer_schema_performance_many_rows() {
         use std::time::Instant;
@@ -1,61 +1,777 @@
 use std::fs::File;
-use std::io::{self, BufReader};
-use std::path::PathBuf;
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use csv_sql_inference::{
-    generate_sql, generate_sqlmodel_python, infer_schema, python_generator::PkStrategy,
+    Dialect, InferenceOptions, IntegerPolicy, SqlFormat, SqlOptions, SqlType, TableSchema,
+    enum_check_values, explain_schema, generate_arrow_schema_to, generate_avro_schema_to,
+    generate_column_mapping_to, generate_diesel_schema_to, generate_django_model_to,
+    generate_json_schema_to, generate_protobuf_to, generate_python_to, generate_rust_struct_to,
+    generate_sql_with_options_to, generate_typescript_to, hoist_columns,
+    infer_schema_with_nullability, infer_schema_with_stats, open_path,
+    python_generator::{PkStrategy, PythonFlavor, PythonGenOptions, PythonTarget},
+    stats_column_comments, topological_order, validate_csv,
 };
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// path to the csv file
-    #[arg(required = true)]
-    file_path: PathBuf,
+    /// path to the csv file; `-` (or no path, when the csv is piped in) reads from stdin
+    file_path: Option<PathBuf>,
 
-    /// generate python sqlmodel code instead of sql ddl
+    /// field delimiter, a single character such as `;` (use `\t` or `tab` for tsv files)
+    #[arg(long, default_value = ",", value_parser = parse_byte_char)]
+    delimiter: u8,
+
+    /// treat the first row as data and name the columns col_1, col_2, ...
+    #[arg(long)]
+    no_header: bool,
+
+    /// names for the columns of a file without a header row, e.g. `id,name`
+    #[arg(long, value_delimiter = ',', requires = "no_header")]
+    columns: Vec<String>,
+
+    /// discard this many leading lines (e.g. a title row) before the header
+    #[arg(long, default_value_t = 0)]
+    skip_rows: usize,
+
+    /// treat this value as null in addition to empty cells, e.g. `NA` or `#*` (repeatable)
+    #[arg(long = "null-token")]
+    null_tokens: Vec<String>,
+
+    /// infer from the first N records only; later rows might not fit the guessed types
+    #[arg(long = "sample", value_name = "N")]
+    sample_rows: Option<usize>,
+
+    /// after the --sample, read the remaining records only to widen types they do not fit
+    #[arg(long, requires = "sample_rows")]
+    verify_tail: bool,
+
+    /// take the --sample from the last N records instead of the first
+    #[arg(long, requires = "sample_rows", conflicts_with = "verify_tail")]
+    sample_tail: bool,
+
+    /// number of threads used for inference (1 infers sequentially); defaults to all cores
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+
+    /// tolerate rows with missing (treated as empty) or extra (ignored) fields
+    #[arg(long)]
+    flexible: bool,
+
+    /// count integral scientific notation such as `1E3` as integers instead of floats
+    #[arg(long)]
+    scientific_integers: bool,
+
+    /// accept `NaN` and `inf` as float values instead of text
+    #[arg(long)]
+    allow_non_finite_floats: bool,
+
+    /// accept this digit grouping separator in numbers, e.g. `,` for `"1,234,567"`
+    #[arg(long, value_name = "CHAR")]
+    thousands_separator: Option<char>,
+
+    /// the decimal separator of numbers, e.g. `,` for `3,14`
+    #[arg(long, value_name = "CHAR", default_value = ".")]
+    decimal_separator: char,
+
+    /// infer DECIMAL(p,s) for currency amounts such as `$1,200.00`
+    #[arg(long)]
+    detect_money: bool,
+
+    /// a currency symbol recognized by --detect-money (repeatable; default `$`, `€`, `£`, `¥`)
+    #[arg(long = "currency-symbol", value_name = "SYMBOL")]
+    currency_symbols: Vec<String>,
+
+    /// values meaning true in a boolean column, e.g. `yes,y` (default `true,t,1`); matched
+    /// case-insensitively after trimming
+    #[arg(long, value_name = "VALUES", value_delimiter = ',')]
+    true_values: Vec<String>,
+
+    /// values meaning false in a boolean column, e.g. `no,n` (default `false,f,0`)
+    #[arg(long, value_name = "VALUES", value_delimiter = ',')]
+    false_values: Vec<String>,
+
+    /// infer a percentage (float) column when every value ends in `%`, e.g. `3.5%`
+    #[arg(long)]
+    detect_percentages: bool,
+
+    /// infer an INET column when every value is an ipv4 or ipv6 address
+    #[arg(long)]
+    detect_ip_addresses: bool,
+
+    /// infer a JSON column when every value is a json object or array
+    #[cfg(feature = "json")]
+    #[arg(long)]
+    detect_json: bool,
+
+    /// force the type of a column instead of inferring it, e.g. `zip=VARCHAR` (sized to the data) or
+    /// `code=VARCHAR(10)` (repeatable)
+    #[arg(long = "force-type", value_name = "COLUMN=TYPE", value_parser = parse_force_type)]
+    force_types: Vec<(String, SqlType)>,
+
+    /// the type of columns that are empty in every record, e.g. `INTEGER` (default: VARCHAR)
+    #[arg(long, value_name = "TYPE")]
+    empty_type: Option<SqlType>,
+
+    /// infer CHAR for this column if it holds strings, even with --prefer-varchar (repeatable)
+    #[arg(long = "char", value_name = "COLUMN")]
+    char_columns: Vec<String>,
+
+    /// infer VARCHAR for this column if it holds strings, even when they share a length
+    /// (repeatable)
+    #[arg(long = "varchar", value_name = "COLUMN")]
+    varchar_columns: Vec<String>,
+
+    /// infer CHAR(n) for columns of hex digests (md5, sha, ...), even where some are all digits
+    #[arg(long)]
+    detect_hex_digests: bool,
+
+    /// infer VARCHAR instead of CHAR(n) for text columns whose values all have the same length
     #[arg(long)]
+    prefer_varchar: bool,
+
+    /// emit BIGINT for every integer column instead of INTEGER where the values fit
+    #[arg(long)]
+    always_bigint: bool,
+
+    /// quote character around fields containing delimiters, e.g. `'`
+    #[arg(long, default_value = "\"", value_parser = parse_byte_char)]
+    quote: u8,
+
+    /// escape character for quotes inside quoted fields, e.g. `\`
+    #[arg(long, value_parser = parse_byte_char)]
+    escape: Option<u8>,
+
+    /// skip lines starting with this character, e.g. `#`
+    #[arg(long, value_parser = parse_byte_char)]
+    comment_char: Option<u8>,
+
+    /// generate python sqlmodel code instead of sql ddl
+    #[arg(long, group = "generator")]
     python: bool,
 
-    /// specify an existing column name to use as the primary key for python sqlmodel
-    #[arg(long, group = "pk_option")]
-    pk_column: Option<String>,
+    /// spell optional python types as `Optional[X]` for python 3.8/3.9 instead of `X | None`
+    #[arg(long)]
+    py_optional: bool,
+
+    /// generate a python pydantic basemodel instead of sql ddl
+    #[arg(long, group = "generator")]
+    pydantic: bool,
+
+    /// generate a python stdlib dataclass instead of sql ddl
+    #[arg(long, group = "generator")]
+    dataclass: bool,
+
+    /// snake_case --pydantic and --dataclass field names word by word (`OrderID` becomes
+    /// `order_id`) instead of lowercasing them; sql always quotes the headers verbatim
+    #[arg(long)]
+    snake_case_fields: bool,
+
+    /// generate a typescript interface instead of sql ddl
+    #[arg(long, group = "generator")]
+    typescript: bool,
+
+    /// generate a rust struct with serde derives instead of sql ddl
+    #[arg(long, group = "generator")]
+    rust: bool,
+
+    /// generate a json schema describing one record instead of sql ddl
+    #[arg(long, group = "generator")]
+    json_schema: bool,
+
+    /// generate an avro record schema instead of sql ddl
+    #[arg(long, group = "generator")]
+    avro: bool,
+
+    /// generate a textual arrow (parquet-compatible) schema instead of sql ddl
+    #[arg(long, group = "generator")]
+    arrow: bool,
+
+    /// generate a proto3 message definition instead of sql ddl
+    #[arg(long, group = "generator")]
+    proto: bool,
 
-    /// specify a name for a new auto-generated identity primary key for python sqlmodel
-    #[arg(long, group = "pk_option")]
+    /// generate a diesel `table!` schema instead of sql ddl
+    #[arg(long, group = "generator")]
+    diesel: bool,
+
+    /// generate a django model instead of sql ddl
+    #[arg(long, group = "generator")]
+    django: bool,
+
+    /// name of the generated table (and python/rust/... type); defaults to the file name
+    #[arg(long, value_name = "NAME")]
+    table_name: Option<String>,
+
+    /// infer every `.csv` file in this directory and print their outputs one after another,
+    /// each named after its file; files that fail are reported and skipped
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["file_path", "table_name", "explain", "validate"]
+    )]
+    dir: Option<PathBuf>,
+
+    /// print the columns in a machine-readable format instead of sql ddl
+    #[arg(long, value_enum, group = "generator")]
+    format: Option<Format>,
+
+    /// write the output to this file instead of stdout
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// an existing column to use as the primary key (repeatable or comma-separated for a
+    /// composite key)
+    #[arg(long, group = "pk_option", value_delimiter = ',')]
+    pk_column: Vec<String>,
+
+    /// move the --pk-column column(s) to the top of the generated output instead of keeping
+    /// their csv position
+    #[arg(long)]
+    pk_first: bool,
+
+    /// specify a name for a new auto-generated identity primary key (python sqlmodel, django and
+    /// sql ddl); diesel schemas only key existing columns, so it cannot be combined with --diesel
+    #[arg(long, group = "pk_option", conflicts_with = "diesel")]
     pk_create: Option<String>,
+
+    /// report progress and the inferred columns on stderr
+    #[arg(short, long, group = "verbosity")]
+    verbose: bool,
+
+    /// do not print anything but the output, not even warnings
+    #[arg(short, long, group = "verbosity")]
+    quiet: bool,
+
+    /// print how each column's type was inferred instead of generating code
+    #[arg(long)]
+    explain: bool,
+
+    /// only check that every row has as many fields as the header, print a report and exit
+    /// with status 1 if any does not
+    #[arg(long, conflicts_with = "explain")]
+    validate: bool,
+
+    /// sql dialect used for the generated ddl (generic, postgres, mysql, sqlite)
+    #[arg(long, default_value_t = Dialect::Generic)]
+    dialect: Dialect,
+
+    /// add a CHECK (col IN (...)) constraint to the sql ddl for low-cardinality text columns
+    #[arg(long)]
+    check_constraints: bool,
+
+    /// document each column's inferred type, null count and longest value as a column comment
+    /// in the sql ddl
+    #[arg(long)]
+    comments: bool,
+
+    /// emit at least this length for VARCHAR columns in the sql ddl, e.g. 255
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    varchar_min: usize,
+
+    /// emit TEXT instead of VARCHAR for columns longer than this in the sql ddl
+    #[arg(long, value_name = "N")]
+    varchar_max: Option<usize>,
+
+    /// indent the column definitions of the sql ddl by this many spaces
+    #[arg(long, value_name = "N", default_value_t = 2)]
+    indent: usize,
+
+    /// put the opening parenthesis of the sql ddl on a line of its own
+    #[arg(long)]
+    paren_newline: bool,
+
+    /// start the column definitions of the sql ddl with their comma instead of ending them
+    /// with it
+    #[arg(long)]
+    leading_commas: bool,
+
+    /// leave table and column names of the sql ddl unquoted when they are lowercase and no
+    /// reserved word
+    #[arg(long)]
+    no_quote_identifiers: bool,
+
+    /// append a table option to the sql ddl, e.g. `fillfactor=90` for postgres (`WITH (...)`)
+    /// or `ROW_FORMAT=DYNAMIC` for mysql (repeatable)
+    #[arg(long = "table-option", value_name = "KEY=VALUE", value_parser = parse_table_option)]
+    table_options: Vec<(String, String)>,
+}
+
+/// a machine-readable output format for `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// a json array of `{"name", "sql_type", "nullable"}` objects
+    Json,
+}
+
+impl Cli {
+    /// returns true if the output is sql ddl, i.e. no other generator was selected.
+    fn writes_sql(&self) -> bool {
+        !(self.python
+            || self.pydantic
+            || self.dataclass
+            || self.typescript
+            || self.rust
+            || self.json_schema
+            || self.avro
+            || self.arrow
+            || self.proto
+            || self.format.is_some()
+            || self.django
+            || self.diesel)
+    }
+
+    /// the `--table-name`, or else the file stem of the input (`my_table` if it has none, as
+    /// for stdin). a `.gz` extension is dropped first, so `sales.csv.gz` is named `sales`.
+    fn table_name(&self) -> &str {
+        self.table_name.as_deref().unwrap_or_else(|| {
+            let file_path = match &self.file_path {
+                Some(file_path) if !self.reads_stdin() => file_path,
+                _ => return "my_table",
+            };
+            let path = match file_path.extension() {
+                Some(extension) if extension.eq_ignore_ascii_case("gz") => {
+                    Path::new(file_path.file_stem().unwrap_or_default())
+                }
+                _ => file_path.as_path(),
+            };
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("my_table")
+        })
+    }
+
+    /// true if the csv is read from stdin: the path is `-` or missing.
+    fn reads_stdin(&self) -> bool {
+        self.file_path
+            .as_deref()
+            .is_none_or(|path| path == Path::new("-"))
+    }
+
+    /// opens the input, see `file_path`.
+    fn open_input(&self) -> io::Result<Box<dyn Read + Send>> {
+        match &self.file_path {
+            Some(path) if !self.reads_stdin() => open_path(path),
+            // without a path, waiting on an interactive terminal would look like a hang.
+            None if io::stdin().is_terminal() => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no csv file given: pass a path, or pipe the csv into stdin",
+            )),
+            _ => Ok(Box::new(io::stdin())),
+        }
+    }
+}
+
+/// parses a single-byte character argument, accepting `\t` and `tab` for a tab.
+fn parse_byte_char(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ => match s.as_bytes() {
+            [byte] => Ok(*byte),
+            _ => Err(format!("expected a single ascii character, got '{}'", s)),
+        },
+    }
+}
+
+/// parses a `column=TYPE` type override.
+fn parse_force_type(s: &str) -> Result<(String, SqlType), String> {
+    let (column, sql_type) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=TYPE, got '{}'", s))?;
+    Ok((column.trim().to_string(), sql_type.parse()?))
+}
+
+/// parses a `key=value` table option.
+fn parse_table_option(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", s))?;
+    Ok((key.trim().to_string(), value.trim().to_string()))
 }
 
 fn main() -> io::Result<()> {
     let cli = Cli::parse();
+    // log output goes to stderr, so stdout only ever carries the generated code.
+    env_logger::Builder::new()
+        .filter_level(if cli.quiet {
+            log::LevelFilter::Off
+        } else if cli.verbose {
+            log::LevelFilter::Info
+        } else {
+            log::LevelFilter::Warn
+        })
+        .format_timestamp(None)
+        .format_target(false)
+        .target(env_logger::Target::Stderr)
+        .init();
+    let mut options = InferenceOptions {
+        delimiter: cli.delimiter,
+        has_headers: !cli.no_header,
+        column_names: cli.columns.clone(),
+        skip_rows: cli.skip_rows,
+        quote: cli.quote,
+        escape: cli.escape,
+        comment: cli.comment_char,
+        null_tokens: cli.null_tokens.clone(),
+        sample_rows: cli.sample_rows,
+        verify_tail: cli.verify_tail,
+        sample_from_tail: cli.sample_tail,
+        threads: cli.threads,
+        flexible: cli.flexible,
+        scientific_integers: cli.scientific_integers,
+        allow_non_finite_floats: cli.allow_non_finite_floats,
+        thousands_separator: cli.thousands_separator,
+        decimal_separator: cli.decimal_separator,
+        detect_money: cli.detect_money,
+        detect_percentages: cli.detect_percentages,
+        detect_ip_addresses: cli.detect_ip_addresses,
+        detect_hex_digests: cli.detect_hex_digests,
+        prefer_varchar_over_char: cli.prefer_varchar,
+        integer_policy: if cli.always_bigint {
+            IntegerPolicy::AlwaysBigInt
+        } else {
+            IntegerPolicy::Strict
+        },
+        type_overrides: cli.force_types.iter().cloned().collect(),
+        char_columns: cli.char_columns.clone(),
+        varchar_columns: cli.varchar_columns.clone(),
+        default_type_for_empty: cli.empty_type.clone(),
+        ..InferenceOptions::default()
+    };
+    #[cfg(feature = "json")]
+    {
+        options.detect_json = cli.detect_json;
+    }
+    if !cli.currency_symbols.is_empty() {
+        options.currency_symbols = cli.currency_symbols.clone();
+    }
+    if !cli.true_values.is_empty() {
+        options.true_values = cli.true_values.clone();
+    }
+    if !cli.false_values.is_empty() {
+        options.false_values = cli.false_values.clone();
+    }
+
+    let mut out: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
 
-    let file = File::open(&cli.file_path)?;
-    let reader = BufReader::new(file);
-
-    let (headers, inferred_types) = infer_schema(reader)?;
-    // derive table name from the file path, or use a default
-    let table_name = cli
-        .file_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("my_table");
-
-    if cli.python {
-        let pk_strategy = if let Some(col_name) = cli.pk_column {
-            PkStrategy::ExistingColumn(col_name)
-        } else if let Some(col_name) = cli.pk_create {
-            PkStrategy::CreateColumn(col_name)
+    if let Some(dir) = &cli.dir {
+        let failures = write_dir(&mut out, &cli, &options, dir)?;
+        out.flush()?;
+        if failures > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let table_name = cli.table_name().to_string();
+    // the library buffers its input itself.
+    let reader = cli.open_input()?;
+
+    if cli.validate {
+        let report = validate_csv(reader, &options)?;
+        writeln!(out, "{}", report)?;
+        out.flush()?;
+        if !report.is_valid() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.explain {
+        let (headers, explanations) = explain_schema(reader, &options)?;
+        for (header, explanation) in headers.iter().zip(explanations.iter()) {
+            writeln!(out, "column \"{}\":", header.trim())?;
+            for line in explanation.lines() {
+                writeln!(out, "  {}", line)?;
+            }
+        }
+        return out.flush();
+    }
+
+    write_table(&mut out, &cli, &options, reader, &table_name)?;
+    writeln!(out)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// writes the output for every `.csv` file of `dir`, separated by blank lines, and returns the
+/// number of files that failed. a failing file is logged and skipped. sql ddl is ordered by
+/// `topological_order`, so referenced tables come first; other output is in file name order.
+fn write_dir<W: Write>(
+    out: &mut W,
+    cli: &Cli,
+    options: &InferenceOptions,
+    dir: &Path,
+) -> io::Result<usize> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+    });
+    paths.sort();
+
+    let mut failures = 0;
+    let mut schemas: Vec<TableSchema> = Vec::new();
+    let mut outputs: Vec<Vec<u8>> = Vec::new();
+    for path in &paths {
+        let table_name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("my_table");
+        // buffered, so a file that fails halfway leaves no partial output behind.
+        let mut table = Vec::new();
+        let result = open_path(path)
+            .and_then(|reader| write_table(&mut table, cli, options, reader, table_name));
+        match result {
+            Ok(schema) => {
+                schemas.push(schema);
+                outputs.push(table);
+            }
+            Err(err) => {
+                log::error!("{}: {}", path.display(), err);
+                failures += 1;
+            }
+        }
+    }
+    if paths.is_empty() {
+        log::warn!("no .csv files in {}", dir.display());
+    }
+
+    // tables are created after the tables their `*_id` columns refer to.
+    let order: Vec<usize> = if cli.writes_sql() {
+        let (order, warnings) = topological_order(&schemas);
+        for warning in &warnings {
+            writeln!(out, "-- warning: {}", warning)?;
+        }
+        order
+    } else {
+        (0..outputs.len()).collect()
+    };
+    for (n, i) in order.into_iter().enumerate() {
+        if n > 0 {
+            writeln!(out)?;
+        }
+        out.write_all(&outputs[i])?;
+        writeln!(out)?;
+    }
+    Ok(failures)
+}
+
+/// infers the schema of one csv and writes the selected output for it, without a trailing
+/// newline.
+fn write_table<W: Write, R: Read>(
+    out: &mut W,
+    cli: &Cli,
+    options: &InferenceOptions,
+    reader: R,
+    table_name: &str,
+) -> io::Result<TableSchema> {
+    let mut sql_options = SqlOptions {
+        create_primary_key: cli.pk_create.clone(),
+        varchar_min: cli.varchar_min,
+        varchar_max: cli.varchar_max,
+        format: SqlFormat {
+            indent: cli.indent,
+            paren_on_new_line: cli.paren_newline,
+            leading_commas: cli.leading_commas,
+        },
+        unquoted_identifiers: cli.no_quote_identifiers,
+        table_options: cli.table_options.clone(),
+        ..SqlOptions::default()
+    };
+    // profiling every column costs memory, so the stats are only collected when the check
+    // constraints or column comments need them.
+    let (mut headers, mut inferred_types, mut nullability) =
+        if cli.check_constraints || cli.comments {
+            let (headers, stats) = infer_schema_with_stats(reader, options)?;
+            if cli.check_constraints {
+                sql_options.check_values = enum_check_values(&headers, &stats);
+            }
+            if cli.comments {
+                sql_options.column_comments = stats_column_comments(&headers, &stats);
+            }
+            let nullability = stats
+                .iter()
+                .map(|stats| stats.row_count == 0 || stats.null_count > 0)
+                .collect();
+            let types = stats.into_iter().map(|stats| stats.sql_type).collect();
+            (headers, types, nullability)
         } else {
-            PkStrategy::None
+            infer_schema_with_nullability(reader, options)?
         };
-        let python_code =
-            generate_sqlmodel_python(table_name, &headers, &inferred_types, &pk_strategy);
-        println!("{}", python_code);
+    if cli.pk_first {
+        (headers, inferred_types, nullability) =
+            hoist_columns(&headers, &inferred_types, &nullability, &cli.pk_column);
+    }
+    let pk_strategy = match (cli.pk_column.as_slice(), cli.pk_create.clone()) {
+        ([], Some(col_name)) => PkStrategy::CreateColumn(col_name),
+        ([], None) => PkStrategy::None,
+        ([col_name], _) => PkStrategy::ExistingColumn(col_name.clone()),
+        (col_names, _) => PkStrategy::Composite(col_names.to_vec()),
+    };
+    // a mistyped --pk-column would otherwise silently produce a model without a key.
+    pk_strategy.validate(&headers)?;
+    // the ddl has to name the key columns as they appear in the csv, not as typed.
+    let key_headers = pk_strategy.key_headers(&headers);
+    sql_options.primary_key = key_headers.clone();
+
+    let python_flavor = if cli.python {
+        Some(PythonFlavor::SqlModel)
+    } else if cli.pydantic {
+        Some(PythonFlavor::Pydantic)
+    } else if cli.dataclass {
+        Some(PythonFlavor::Dataclass)
     } else {
-        let sql_statement = generate_sql(table_name, &headers, &inferred_types);
-        println!("{}", sql_statement);
+        None
+    };
+
+    if let Some(flavor) = python_flavor {
+        let python_target = if cli.py_optional {
+            PythonTarget::Py38
+        } else {
+            PythonTarget::Py310
+        };
+        let python_options = PythonGenOptions::default()
+            .with_flavor(flavor)
+            .with_pk_strategy(pk_strategy)
+            .with_python_target(python_target)
+            .with_nullability(nullability)
+            .with_snake_case_fields(cli.snake_case_fields);
+        generate_python_to(out, table_name, &headers, &inferred_types, &python_options)?;
+    } else if cli.typescript {
+        generate_typescript_to(out, table_name, &headers, &inferred_types, &nullability)?;
+    } else if cli.rust {
+        generate_rust_struct_to(out, table_name, &headers, &inferred_types, &nullability)?;
+    } else if cli.json_schema {
+        generate_json_schema_to(out, table_name, &headers, &inferred_types, &nullability)?;
+    } else if cli.avro {
+        generate_avro_schema_to(out, table_name, &headers, &inferred_types, &nullability)?;
+    } else if cli.arrow {
+        generate_arrow_schema_to(out, &headers, &inferred_types, &nullability)?;
+    } else if cli.proto {
+        generate_protobuf_to(out, table_name, &headers, &inferred_types, &nullability)?;
+    } else if cli.format == Some(Format::Json) {
+        generate_column_mapping_to(out, &headers, &inferred_types, &nullability)?;
+    } else if cli.django {
+        generate_django_model_to(
+            out,
+            table_name,
+            &headers,
+            &inferred_types,
+            &nullability,
+            &pk_strategy,
+        )?;
+    } else if cli.diesel {
+        generate_diesel_schema_to(
+            out,
+            table_name,
+            &headers,
+            &inferred_types,
+            &nullability,
+            &key_headers.iter().map(String::as_str).collect::<Vec<_>>(),
+        )?;
+    } else {
+        generate_sql_with_options_to(
+            out,
+            table_name,
+            &headers,
+            &inferred_types,
+            cli.dialect,
+            &sql_options,
+        )?;
     }
+    Ok(TableSchema {
+        name: table_name.to_string(),
+        headers,
+        types: inferred_types,
+    })
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_pk_create_conflicts_with_diesel() {
+        // the diesel schema would otherwise be keyed on the first csv column without a word.
+        let result = Cli::try_parse_from([
+            "csv_sql_inference",
+            "/tmp/people.csv",
+            "--pk-create",
+            "id",
+            "--diesel",
+        ]);
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+        assert!(
+            Cli::try_parse_from(["csv_sql_inference", "/tmp/people.csv", "--pk-create", "id"])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_table_name_defaults_to_file_stem() {
+        let cli = Cli::parse_from(["csv_sql_inference", "/tmp/abc123.csv"]);
+        assert_eq!(cli.table_name(), "abc123");
+        let cli = Cli::parse_from(["csv_sql_inference", "/tmp/sales.csv.gz"]);
+        assert_eq!(cli.table_name(), "sales");
+        let cli = Cli::parse_from(["csv_sql_inference", "-"]);
+        assert!(cli.reads_stdin());
+        assert_eq!(cli.table_name(), "my_table");
+        let cli = Cli::parse_from(["csv_sql_inference", "--table-name", "piped"]);
+        assert!(cli.reads_stdin());
+        assert_eq!(cli.table_name(), "piped");
+    }
+
+    #[test]
+    fn test_table_name_flag_flows_into_generators() {
+        let cli = Cli::parse_from([
+            "csv_sql_inference",
+            "/tmp/abc123.csv",
+            "--table-name",
+            "user_accounts",
+        ]);
+        let table_name = cli.table_name();
+        assert_eq!(table_name, "user_accounts");
+
+        let headers = csv::StringRecord::from(vec!["id"]);
+        let types = vec![SqlType::Integer];
+        assert!(
+            csv_sql_inference::generate_sql_with_options(
+                table_name,
+                &headers,
+                &types,
+                Dialect::Generic,
+                &SqlOptions::default()
+            )
+            .starts_with("CREATE TABLE \"user_accounts\" (")
+        );
+        let python = csv_sql_inference::generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &[false],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
+        assert!(python.contains("class UserAccounts(SQLModel, table=True):"));
+    }
 }
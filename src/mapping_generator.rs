@@ -0,0 +1,116 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{json_string, unique_names, write_to_string};
+
+/// renders a sql type as a json object tagged by its `SqlType::kind`, carrying the length of
+/// char/varchar and the precision and scale of decimal columns.
+fn sql_type_json(sql_type: &SqlType) -> String {
+    let kind = json_string(sql_type.kind());
+    match sql_type {
+        SqlType::Char(len) | SqlType::Varchar(len) => {
+            format!("{{\"kind\": {}, \"length\": {}}}", kind, len)
+        }
+        SqlType::Decimal(precision, scale) => format!(
+            "{{\"kind\": {}, \"precision\": {}, \"scale\": {}}}",
+            kind, precision, scale
+        ),
+        _ => format!("{{\"kind\": {}}}", kind),
+    }
+}
+
+/// generates a json array describing each column as `{"name", "sql_type", "nullable"}`, a
+/// machine-readable form of the inferred schema for other tools. names are the trimmed headers,
+/// with repeated ones made unique like the sql columns (`id, id` becomes `id, id_2`).
+/// with the `serde` feature, each `sql_type` object deserializes back into a `SqlType`.
+pub fn generate_column_mapping(
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| generate_column_mapping_to(writer, headers, types, nullability))
+}
+
+/// writes the array of `generate_column_mapping` to `writer`.
+pub fn generate_column_mapping_to<W: Write>(
+    writer: &mut W,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writer.write_all(b"[")?;
+    let names = unique_names(headers.iter().map(|header| header.trim().to_string()));
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        write!(
+            writer,
+            "\n  {{\"name\": {}, \"sql_type\": {}, \"nullable\": {}}}",
+            json_string(name),
+            sql_type_json(&types[i]),
+            nullability[i]
+        )?;
+    }
+    if !headers.is_empty() {
+        writer.write_all(b"\n")?;
+    }
+    writer.write_all(b"]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_column_mapping() {
+        let headers = StringRecord::from(vec!["id", " name ", "price"]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::Varchar(5),
+            SqlType::Decimal(6, 2),
+        ];
+        let nullability = vec![false, true, false];
+        let mapping = generate_column_mapping(&headers, &types, &nullability);
+
+        let json: serde_json::Value = serde_json::from_str(&mapping).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"name": "id", "sql_type": {"kind": "integer"}, "nullable": false},
+                {"name": "name", "sql_type": {"kind": "varchar", "length": 5}, "nullable": true},
+                {
+                    "name": "price",
+                    "sql_type": {"kind": "decimal", "precision": 6, "scale": 2},
+                    "nullable": false
+                }
+            ])
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_column_mapping_round_trips_through_serde() {
+        let headers = StringRecord::from(vec!["code", "total"]);
+        let types = vec![SqlType::Varchar(12), SqlType::Decimal(10, 2)];
+        let mapping = generate_column_mapping(&headers, &types, &[true, false]);
+
+        let json: serde_json::Value = serde_json::from_str(&mapping).unwrap();
+        let parsed: Vec<SqlType> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|column| serde_json::from_value(column["sql_type"].clone()).unwrap())
+            .collect();
+        assert_eq!(parsed, types);
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let mapping = generate_column_mapping(&headers, &types, &[false; 3]);
+        assert!(mapping.contains("{\"name\": \"id\", "));
+        assert!(mapping.contains("{\"name\": \"id_2\", "));
+    }
+}
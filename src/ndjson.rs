@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read};
+
+use csv::StringRecord;
+use serde_json::Value;
+
+use crate::type_inference::ColumnInferenceState;
+use crate::{InferenceOptions, SqlType};
+
+/// renders a json value as the text inference sees: strings unquoted, `null` as an empty
+/// (missing) value, and nested objects or arrays as their json text.
+fn value_text(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::Null => Cow::Borrowed(""),
+        Value::String(s) => Cow::Borrowed(s),
+        Value::Bool(b) => Cow::Borrowed(if *b { "true" } else { "false" }),
+        other => Cow::Owned(other.to_string()),
+    }
+}
+
+/// infers a schema from newline-delimited json (json lines): one json object per line, blank
+/// lines are skipped. see `infer_schema_ndjson_with_options`.
+pub fn infer_schema_ndjson<R: Read>(
+    reader: R,
+) -> io::Result<(StringRecord, Vec<SqlType>, Vec<bool>)> {
+    infer_schema_ndjson_with_options(reader, &InferenceOptions::default())
+}
+
+/// infers a schema from newline-delimited json, with one column per key of any object. the
+/// columns are ordered by first appearance (the keys within one object in `serde_json`'s map
+/// order). each value is inferred from its text like a csv value: numbers and booleans as
+/// written, strings without their quotes, nested objects and arrays as json. a key that is
+/// missing from an object, or `null`, counts as an empty value and makes the column nullable.
+/// of `options`, only the value parsing, detection, null token, override and `sample_rows`
+/// settings apply.
+pub fn infer_schema_ndjson_with_options<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<(StringRecord, Vec<SqlType>, Vec<bool>)> {
+    let mut keys: Vec<String> = Vec::new();
+    let mut states: Vec<ColumnInferenceState> = Vec::new();
+    let mut key_index: HashMap<String, usize> = HashMap::new();
+    let mut records_read = 0;
+
+    let mut lines = BufReader::new(reader).lines().enumerate();
+    while records_read < options.sample_rows.unwrap_or(usize::MAX) {
+        let Some((line_index, line)) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", line_index + 1, message),
+            )
+        };
+        let object = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(object)) => object,
+            Ok(_) => return Err(invalid("expected a json object".to_string())),
+            Err(err) => return Err(invalid(err.to_string())),
+        };
+
+        let mut seen = vec![false; states.len()];
+        for (key, value) in &object {
+            let i = *key_index.entry(key.clone()).or_insert_with(|| {
+                // a new key was missing from every earlier record.
+                let mut state = ColumnInferenceState::new(options);
+                for _ in 0..records_read {
+                    state.observe("");
+                }
+                keys.push(key.clone());
+                states.push(state);
+                seen.push(false);
+                states.len() - 1
+            });
+            let text = value_text(value);
+            let text = if options.is_null_token(&text) {
+                ""
+            } else {
+                &text
+            };
+            states[i].observe(text);
+            seen[i] = true;
+        }
+        for (state, _) in states.iter_mut().zip(&seen).filter(|(_, seen)| !**seen) {
+            state.observe("");
+        }
+        records_read += 1;
+    }
+
+    if keys.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "json lines input is empty or has no keys",
+        ));
+    }
+    let types = keys
+        .iter()
+        .zip(&states)
+        .map(|(key, state)| options.resolve_type(key, state.finalize(), state.max_len()))
+        .collect();
+    let nullability = states
+        .iter()
+        .map(ColumnInferenceState::is_nullable)
+        .collect();
+    Ok((StringRecord::from(keys), types, nullability))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_infer_schema_ndjson_missing_field_is_nullable() {
+        let data = r#"{"id": 1, "name": "Alice", "active": true}
+{"id": 2, "active": false}
+"#;
+        let (headers, types, nullability) = infer_schema_ndjson(Cursor::new(data)).unwrap();
+        // keys within one object come in map order, so compare the columns by name.
+        let mut columns: Vec<(&str, &SqlType, bool)> = headers
+            .iter()
+            .zip(&types)
+            .zip(nullability)
+            .map(|((header, sql_type), nullable)| (header, sql_type, nullable))
+            .collect();
+        columns.sort_by_key(|(name, _, _)| *name);
+        assert_eq!(
+            columns,
+            vec![
+                ("active", &SqlType::Boolean, false),
+                ("id", &SqlType::Integer, false),
+                ("name", &SqlType::Char(5), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer_schema_ndjson_values() {
+        let data = "{\"price\": 1.5, \"tags\": [\"a\"], \"note\": null}\n\n\
+                    {\"price\": 2, \"tags\": [], \"note\": \"x\", \"late\": \"2024-01-01\"}\n";
+        let options = InferenceOptions::default().with_json_detection(true);
+        let (headers, types, nullability) =
+            infer_schema_ndjson_with_options(Cursor::new(data), &options).unwrap();
+        let late = headers.iter().position(|key| key == "late").unwrap();
+        assert_eq!(types[late], SqlType::Date);
+        assert!(nullability[late]);
+        let price = headers.iter().position(|key| key == "price").unwrap();
+        assert_eq!(types[price], SqlType::Float);
+        let tags = headers.iter().position(|key| key == "tags").unwrap();
+        assert_eq!(types[tags], SqlType::Json);
+        let note = headers.iter().position(|key| key == "note").unwrap();
+        assert!(nullability[note]);
+    }
+
+    #[test]
+    fn test_infer_schema_ndjson_rejects_non_objects() {
+        let err = infer_schema_ndjson(Cursor::new("{\"a\": 1}\n[1, 2]\n")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 2: expected a json object");
+        assert!(infer_schema_ndjson(Cursor::new("{\"a\": 1\n")).is_err());
+        assert!(infer_schema_ndjson(Cursor::new("\n")).is_err());
+    }
+}
@@ -0,0 +1,495 @@
+use std::collections::HashMap;
+
+use crate::SqlType;
+use crate::type_inference::{DEFAULT_CURRENCY_SYMBOLS, DEFAULT_FALSE_VALUES, DEFAULT_TRUE_VALUES};
+
+/// how integer columns are sized, see `InferenceOptions::integer_policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegerPolicy {
+    /// the narrowest type that fits: `Integer` for 32-bit values, `BigInt` beyond.
+    #[default]
+    Strict,
+    /// every integer column is a `BigInt`, so growing values never need a migration.
+    AlwaysBigInt,
+}
+
+/// which checks of the type hierarchy run during inference, see
+/// `InferenceOptions::enabled_types`. every check is enabled by default; a disabled check never
+/// matches, so the column falls through to the next type (e.g. a `1`/`0` column becomes an
+/// `Integer` without `boolean`, and same-length text becomes a `Varchar` without `char`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnabledTypes {
+    pub boolean: bool,
+    pub integer: bool,
+    pub bigint: bool,
+    pub float: bool,
+    pub datetime: bool,
+    pub date: bool,
+    pub char: bool,
+}
+
+impl Default for EnabledTypes {
+    fn default() -> Self {
+        EnabledTypes {
+            boolean: true,
+            integer: true,
+            bigint: true,
+            float: true,
+            datetime: true,
+            date: true,
+            char: true,
+        }
+    }
+}
+
+/// configuration for reading and inferring a csv file.
+/// the default options read standard comma-separated files with a header row; the `with_*`
+/// methods adjust them one at a time, e.g.
+/// `InferenceOptions::default().with_delimiter(b';').with_null_tokens(["NA"])`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferenceOptions {
+    /// the field delimiter byte, e.g. `b','`, `b'\t'` or `b';'`.
+    pub delimiter: u8,
+    /// whether the first row holds the column names. without a header row, the columns are
+    /// named `col_1`, `col_2`, ... and the first row takes part in type inference.
+    pub has_headers: bool,
+    /// the column names of a file without a header row, replacing `col_1`, `col_2`, .... there
+    /// must be one name per field of the first record. ignored if `has_headers` is set.
+    pub column_names: Vec<String>,
+    /// the number of leading lines (e.g. a title or banner) discarded before the header row.
+    pub skip_rows: usize,
+    /// the quote byte surrounding fields that contain delimiters or newlines.
+    pub quote: u8,
+    /// an escape byte for quotes inside quoted fields (e.g. `b'\\'`), used in addition to the
+    /// standard doubled-quote escaping. `None` disables it.
+    pub escape: Option<u8>,
+    /// lines starting with this byte (e.g. `b'#'`) are skipped, including before the header.
+    pub comment: Option<u8>,
+    /// values such as `NA`, `NULL`, `#N/A` or `(null)` that mean "missing". a value matching one
+    /// of them (case-insensitively, ignoring surrounding whitespace) is treated exactly like an
+    /// empty string during inference. a `*` in a token matches any run of characters, so `#*`
+    /// covers spreadsheet errors such as `#N/A` and `#VALUE!`.
+    pub null_tokens: Vec<String>,
+    /// infer from at most this many records. types inferred from a sample are a guess:
+    /// later rows might violate them. `None` reads the whole input.
+    pub sample_rows: Option<usize>,
+    /// after the `sample_rows` records, read the rest of the input only to verify the sampled
+    /// types: a value that does not fit widens the type (e.g. `Integer` to `BigInt` or
+    /// `Float`, and anything to `Varchar`), but the other types are not considered again, so
+    /// the tail is much cheaper to read than a full inference. off by default.
+    pub verify_tail: bool,
+    /// take the `sample_rows` sample from the end of the input instead of its start, for files
+    /// with a summary or header-like block on top. the whole input is read, but only the last
+    /// `sample_rows` records are kept (and `verify_tail` has nothing left to verify). off by
+    /// default.
+    pub sample_from_tail: bool,
+    /// the number of threads used to infer the columns. `None` (or `Some(0)`) uses rayon's
+    /// global pool, `Some(1)` infers sequentially on the calling thread and any other count
+    /// runs the inference on a dedicated pool of that size.
+    pub threads: Option<usize>,
+    /// tolerate records with a different number of fields than the header: missing trailing
+    /// fields count as empty values and extra fields are ignored. off by default, in which
+    /// case such a record is an error.
+    pub flexible: bool,
+    /// count integral values in scientific notation (e.g. `1E3`) as integers instead of
+    /// floats. `1.5E-1` still makes the column a float.
+    pub scientific_integers: bool,
+    /// accept `NaN`, `inf` and `infinity` as floats. off by default, so a column containing
+    /// them is inferred as text.
+    pub allow_non_finite_floats: bool,
+    /// a digit grouping separator (e.g. `','` for `1,234,567`) removed before numeric
+    /// parsing. it is only accepted between groups of three integer digits. since a comma
+    /// also delimits fields, such values have to be quoted.
+    pub thousands_separator: Option<char>,
+    /// the character separating the integer and fractional digits, `'.'` by default. with
+    /// `','` (e.g. `3,14`), a `.` no longer counts as a decimal point; the data then needs a
+    /// different field delimiter or quoted values.
+    pub decimal_separator: char,
+    /// infer `SqlType::Decimal` for columns of currency amounts such as `$1,200.00`, where
+    /// every value carries one of `currency_symbols` before or after the number. off by default.
+    pub detect_money: bool,
+    /// the currency symbols recognized by `detect_money`: `$`, `€`, `£` and `¥` by default.
+    pub currency_symbols: Vec<String>,
+    /// infer `SqlType::Percentage` for columns where every value is a number followed by `%`
+    /// (e.g. `45%`, `3.5%`). off by default.
+    pub detect_percentages: bool,
+    /// infer `SqlType::Inet` for columns where every value is an ipv4 or ipv6 address.
+    /// off by default.
+    pub detect_ip_addresses: bool,
+    /// infer `SqlType::Json` for columns where every value is a json object or array. this
+    /// needs the `json` feature (enabled by default); without it the option has no effect.
+    /// off by default.
+    pub detect_json: bool,
+    /// infer `SqlType::Char` for columns of same-length hex digests such as md5 or sha hashes
+    /// (at least 16 hex digits, with a letter in at least one value), even where some digests
+    /// consist only of digits, and report `SemanticHint::HexDigest` in `ColumnStats`.
+    /// off by default.
+    pub detect_hex_digests: bool,
+    /// infer `Varchar` instead of `Char` for text columns whose values all have the same length
+    /// (e.g. codes that might grow later). off by default, which keeps the `Char(n)` inference.
+    pub prefer_varchar_over_char: bool,
+    /// the values of a boolean column meaning true: `true`, `t` and `1` by default. values are
+    /// trimmed and compared case-insensitively, so with `yes` configured ` Yes ` matches too.
+    /// a column is only `Boolean` if every non-empty value is in `true_values` or
+    /// `false_values`.
+    pub true_values: Vec<String>,
+    /// the values of a boolean column meaning false: `false`, `f` and `0` by default.
+    pub false_values: Vec<String>,
+    /// the checks of the type hierarchy that run; all of them by default. the opt-in checks
+    /// (money, percentages, ...) have their own `detect_*` options.
+    pub enabled_types: EnabledTypes,
+    /// how integer columns are sized: `Strict` (the default) or `AlwaysBigInt`.
+    pub integer_policy: IntegerPolicy,
+    /// report `SemanticHint::Email` in `ColumnStats` for columns of email addresses. this only
+    /// adds metadata (the sql type stays a varchar) and is off by default, since the pattern
+    /// check costs time on every value.
+    pub detect_emails: bool,
+    /// explicit types by column name, replacing whatever was inferred for that column (e.g. to
+    /// keep a zip code column of digits as text). names are compared with the trimmed header.
+    /// a `Char(0)` or `Varchar(0)` (what an unsized `VARCHAR` parses to) is sized to the longest
+    /// value.
+    pub type_overrides: HashMap<String, SqlType>,
+    /// columns whose string type is always `Char`, even with `prefer_varchar_over_char` or
+    /// values of different lengths (then sized to the longest). like `type_overrides`, names
+    /// are compared with the trimmed header; columns inferred as another type keep it.
+    pub char_columns: Vec<String>,
+    /// columns whose string type is always `Varchar`, keeping the inferred length, e.g. for a
+    /// code column whose values only happen to share a length so far.
+    pub varchar_columns: Vec<String>,
+    /// the type of a column without any non-empty value (e.g. `Integer` for a count column that
+    /// is still empty everywhere), instead of a `Varchar` as long as its longest value. this
+    /// includes every column of an input without data rows. `type_overrides` still take
+    /// precedence. `None` by default.
+    pub default_type_for_empty: Option<SqlType>,
+}
+
+impl Default for InferenceOptions {
+    fn default() -> Self {
+        InferenceOptions {
+            delimiter: b',',
+            has_headers: true,
+            column_names: Vec::new(),
+            skip_rows: 0,
+            quote: b'"',
+            escape: None,
+            comment: None,
+            null_tokens: Vec::new(),
+            sample_rows: None,
+            verify_tail: false,
+            sample_from_tail: false,
+            threads: None,
+            flexible: false,
+            scientific_integers: false,
+            allow_non_finite_floats: false,
+            thousands_separator: None,
+            decimal_separator: '.',
+            detect_money: false,
+            currency_symbols: DEFAULT_CURRENCY_SYMBOLS.map(String::from).to_vec(),
+            detect_percentages: false,
+            detect_ip_addresses: false,
+            detect_json: false,
+            detect_hex_digests: false,
+            prefer_varchar_over_char: false,
+            true_values: DEFAULT_TRUE_VALUES.map(String::from).to_vec(),
+            false_values: DEFAULT_FALSE_VALUES.map(String::from).to_vec(),
+            enabled_types: EnabledTypes::default(),
+            integer_policy: IntegerPolicy::Strict,
+            detect_emails: false,
+            type_overrides: HashMap::new(),
+            char_columns: Vec::new(),
+            varchar_columns: Vec::new(),
+            default_type_for_empty: None,
+        }
+    }
+}
+
+impl InferenceOptions {
+    /// sets the field delimiter byte.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// sets whether the first row holds the column names.
+    pub fn with_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// sets the column names of a file without a header row.
+    pub fn with_column_names<I, S>(mut self, column_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.column_names = column_names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// sets the number of leading lines discarded before the header row.
+    pub fn with_skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// sets the quote byte.
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// sets the escape byte for quotes inside quoted fields.
+    pub fn with_escape(mut self, escape: Option<u8>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// sets the byte that marks comment lines.
+    pub fn with_comment(mut self, comment: Option<u8>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// sets the values treated as missing, replacing any previously configured ones.
+    pub fn with_null_tokens<I, S>(mut self, null_tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.null_tokens = null_tokens.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// limits inference to the first `sample_rows` records.
+    pub fn with_sample_rows(mut self, sample_rows: Option<usize>) -> Self {
+        self.sample_rows = sample_rows;
+        self
+    }
+
+    /// verifies the sampled types against the records after `sample_rows`.
+    pub fn with_verify_tail(mut self, verify_tail: bool) -> Self {
+        self.verify_tail = verify_tail;
+        self
+    }
+
+    /// samples the last `sample_rows` records instead of the first.
+    pub fn with_sample_from_tail(mut self, sample_from_tail: bool) -> Self {
+        self.sample_from_tail = sample_from_tail;
+        self
+    }
+
+    /// sets the number of threads used to infer the columns.
+    pub fn with_threads(mut self, threads: Option<usize>) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// sets whether records with a differing number of fields are tolerated.
+    pub fn with_flexible(mut self, flexible: bool) -> Self {
+        self.flexible = flexible;
+        self
+    }
+
+    /// sets whether integral scientific notation counts as integer.
+    pub fn with_scientific_integers(mut self, scientific_integers: bool) -> Self {
+        self.scientific_integers = scientific_integers;
+        self
+    }
+
+    /// sets whether `NaN` and infinities count as floats.
+    pub fn with_non_finite_floats(mut self, allow_non_finite_floats: bool) -> Self {
+        self.allow_non_finite_floats = allow_non_finite_floats;
+        self
+    }
+
+    /// sets the digit grouping separator accepted in numbers.
+    pub fn with_thousands_separator(mut self, thousands_separator: Option<char>) -> Self {
+        self.thousands_separator = thousands_separator;
+        self
+    }
+
+    /// sets the decimal separator of numbers.
+    pub fn with_decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// sets whether currency amounts are inferred as decimals.
+    pub fn with_money_detection(mut self, detect_money: bool) -> Self {
+        self.detect_money = detect_money;
+        self
+    }
+
+    /// sets the currency symbols recognized by money detection.
+    pub fn with_currency_symbols<I, S>(mut self, currency_symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.currency_symbols = currency_symbols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// sets the values of boolean columns, replacing the default `true`/`t`/`1` and
+    /// `false`/`f`/`0`, e.g. `(["yes", "y"], ["no", "n"])` for survey answers.
+    pub fn with_boolean_values<T, F, S>(mut self, true_values: T, false_values: F) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        F: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.true_values = true_values.into_iter().map(Into::into).collect();
+        self.false_values = false_values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// sets whether `45%`-style columns are inferred as percentages.
+    pub fn with_percentage_detection(mut self, detect_percentages: bool) -> Self {
+        self.detect_percentages = detect_percentages;
+        self
+    }
+
+    /// sets whether ip address columns are inferred as `SqlType::Inet`.
+    pub fn with_ip_address_detection(mut self, detect_ip_addresses: bool) -> Self {
+        self.detect_ip_addresses = detect_ip_addresses;
+        self
+    }
+
+    /// sets whether json object and array columns are inferred as `SqlType::Json`.
+    pub fn with_json_detection(mut self, detect_json: bool) -> Self {
+        self.detect_json = detect_json;
+        self
+    }
+
+    /// sets whether hex digest columns are detected.
+    pub fn with_hex_digest_detection(mut self, detect_hex_digests: bool) -> Self {
+        self.detect_hex_digests = detect_hex_digests;
+        self
+    }
+
+    /// sets whether same-length text columns are inferred as `Varchar` instead of `Char`.
+    pub fn with_prefer_varchar_over_char(mut self, prefer_varchar_over_char: bool) -> Self {
+        self.prefer_varchar_over_char = prefer_varchar_over_char;
+        self
+    }
+
+    /// sets which checks of the type hierarchy run.
+    pub fn with_enabled_types(mut self, enabled_types: EnabledTypes) -> Self {
+        self.enabled_types = enabled_types;
+        self
+    }
+
+    /// sets how integer columns are sized.
+    pub fn with_integer_policy(mut self, integer_policy: IntegerPolicy) -> Self {
+        self.integer_policy = integer_policy;
+        self
+    }
+
+    /// sets whether email columns are reported in `ColumnStats`.
+    pub fn with_email_detection(mut self, detect_emails: bool) -> Self {
+        self.detect_emails = detect_emails;
+        self
+    }
+
+    /// forces the type of the column named `column`, replacing the inferred one.
+    pub fn with_type_override(mut self, column: impl Into<String>, sql_type: SqlType) -> Self {
+        self.type_overrides.insert(column.into(), sql_type);
+        self
+    }
+
+    /// makes the string type of the column named `column` a `Char`, see `char_columns`.
+    pub fn with_char_column(mut self, column: impl Into<String>) -> Self {
+        self.char_columns.push(column.into());
+        self
+    }
+
+    /// makes the string type of the column named `column` a `Varchar`, see `varchar_columns`.
+    pub fn with_varchar_column(mut self, column: impl Into<String>) -> Self {
+        self.varchar_columns.push(column.into());
+        self
+    }
+
+    /// sets the type of columns without any non-empty value, see `default_type_for_empty`.
+    pub fn with_default_type_for_empty(mut self, sql_type: Option<SqlType>) -> Self {
+        self.default_type_for_empty = sql_type;
+        self
+    }
+
+    /// returns the type of the column named `header`: its override if there is one, with an
+    /// unsized `Char`/`Varchar` taking `max_len`, and `inferred` otherwise, turned into a
+    /// `Char` or `Varchar` for the `char_columns` and `varchar_columns`.
+    pub(crate) fn resolve_type(&self, header: &str, inferred: SqlType, max_len: usize) -> SqlType {
+        let header = header.trim();
+        let names = |columns: &[String]| columns.iter().any(|column| column.trim() == header);
+        match (self.type_overrides.get(header), inferred) {
+            (Some(SqlType::Char(0)), _) => SqlType::Char(max_len),
+            (Some(SqlType::Varchar(0)), _) => SqlType::Varchar(max_len),
+            (Some(forced), _) => forced.clone(),
+            (None, SqlType::Char(len)) if names(&self.varchar_columns) => SqlType::Varchar(len),
+            (None, SqlType::Varchar(_)) if names(&self.char_columns) => SqlType::Char(max_len),
+            (None, inferred) => inferred,
+        }
+    }
+
+    /// returns true if `value` matches one of the configured null tokens.
+    pub fn is_null_token(&self, value: &str) -> bool {
+        if self.null_tokens.is_empty() {
+            return false;
+        }
+        let value = value.trim();
+        self.null_tokens.iter().any(|token| {
+            let token = token.trim();
+            if token.contains('*') {
+                wildcard_matches(&token.to_lowercase(), &value.to_lowercase())
+            } else {
+                token.eq_ignore_ascii_case(value)
+            }
+        })
+    }
+}
+
+/// returns true if `value` matches `pattern`, where each `*` stands for any run of characters.
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = value.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_token_matching() {
+        let options = InferenceOptions::default().with_null_tokens(["#*", "(null)", "--", "n/a"]);
+        for value in ["#N/A", "#VALUE!", "(NULL)", " (null) ", "--", "N/A"] {
+            assert!(options.is_null_token(value), "{}", value);
+        }
+        for value in ["", "1", "null", "a#b", "---"] {
+            assert!(!options.is_null_token(value), "{}", value);
+        }
+        assert!(!InferenceOptions::default().is_null_token("NA"));
+    }
+
+    #[test]
+    fn test_wildcard_matches() {
+        assert!(wildcard_matches("*", ""));
+        assert!(wildcard_matches("a*c", "abbc"));
+        assert!(wildcard_matches("a*c", "ac"));
+        assert!(wildcard_matches("*x*", "axb"));
+        assert!(!wildcard_matches("a*c", "abcd"));
+        assert!(!wildcard_matches("ab*ba", "aba"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{to_pascal_case, to_snake_case, unique_names, write_to_string};
+
+/// turns a csv header into a snake_case protobuf field name. a leading digit gets an
+/// underscore prefix.
+fn proto_field_name(header: &str) -> String {
+    let name = to_snake_case(header);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// generates a proto3 message definition from a message name, headers, and inferred types.
+/// fields are numbered from 1 in column order, and columns whose `nullability` entry is true
+/// are marked `optional`.
+pub fn generate_protobuf(
+    message_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| {
+        generate_protobuf_to(writer, message_name, headers, types, nullability)
+    })
+}
+
+/// writes the message of `generate_protobuf` to `writer`.
+pub fn generate_protobuf_to<W: Write>(
+    writer: &mut W,
+    message_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writer.write_all(b"syntax = \"proto3\";\n\n")?;
+    writeln!(writer, "message {} {{", to_pascal_case(message_name))?;
+
+    // repeated headers (`id, name, id`) would declare the same field twice.
+    let field_names = unique_names(headers.iter().map(proto_field_name));
+    for (i, field_name) in field_names.iter().enumerate() {
+        // dates and datetimes travel as iso 8601 strings.
+        let proto_type = match &types[i] {
+            SqlType::Integer => "int32",
+            SqlType::BigInt => "int64",
+            SqlType::Float | SqlType::Decimal(..) | SqlType::Percentage => "double",
+            SqlType::Boolean => "bool",
+            SqlType::Date | SqlType::Datetime => "string",
+            // json documents travel as their text.
+            SqlType::Char(_)
+            | SqlType::Varchar(_)
+            | SqlType::Text
+            | SqlType::Inet
+            | SqlType::Json => "string",
+        };
+        let label = if nullability[i] { "optional " } else { "" };
+        writeln!(
+            writer,
+            "  {}{} {} = {};",
+            label,
+            proto_type,
+            field_name,
+            i + 1
+        )?;
+    }
+
+    writer.write_all(b"}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_protobuf() {
+        let headers = StringRecord::from(vec!["id", "Display Name", "score", "joined", "2fa"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Varchar(30),
+            SqlType::Float,
+            SqlType::Date,
+            SqlType::Boolean,
+        ];
+        let nullability = vec![false, true, true, false, false];
+        let expected = "syntax = \"proto3\";
+
+message Users {
+  int64 id = 1;
+  optional string display_name = 2;
+  optional double score = 3;
+  string joined = 4;
+  bool _2fa = 5;
+}";
+        assert_eq!(
+            generate_protobuf("users", &headers, &types, &nullability),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let generated = generate_protobuf("people", &headers, &types, &[false; 3]);
+        assert!(generated.contains("  int32 id = 1;\n  string name = 2;\n  int32 id_2 = 3;\n"));
+    }
+}
@@ -1,114 +1,614 @@
+use std::io::{self, Write};
+
 use super::{SqlType, StringRecord};
-use crate::utils::to_pascal_case;
+use crate::utils::{to_pascal_case, to_snake_case, unique_names, write_to_string};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PkStrategy {
     ExistingColumn(String),
     CreateColumn(String),
+    /// a multi-column key (e.g. for junction tables): every named column is a primary key field.
+    Composite(Vec<String>),
     None,
 }
 
+impl PkStrategy {
+    /// checks that the columns of an `ExistingColumn` or `Composite` key are all among
+    /// `headers`, compared like the generated field names (so `User ID` matches a `user_id`
+    /// header). other strategies always pass.
+    pub fn validate(&self, headers: &StringRecord) -> io::Result<()> {
+        match self.missing_columns(headers).first() {
+            Some(pk_name) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("pk column '{}' not found in csv headers", pk_name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// returns true if `header` is one of the key columns named by this strategy.
+    pub(crate) fn is_key_column(&self, header: &str) -> bool {
+        let header = to_snake_case(header);
+        self.key_names()
+            .iter()
+            .any(|pk_name| header == to_snake_case(pk_name))
+    }
+
+    /// returns the headers of the named key columns, in key order, so that a key given as
+    /// `Order ID` names the `order_id` column in the generated ddl. names that match no header
+    /// are kept as given.
+    pub fn key_headers(&self, headers: &StringRecord) -> Vec<String> {
+        self.key_names()
+            .iter()
+            .map(|pk_name| {
+                matching_header(headers, pk_name)
+                    .unwrap_or(pk_name)
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// returns the named key columns that match none of `headers`.
+    fn missing_columns<'a>(&'a self, headers: &StringRecord) -> Vec<&'a str> {
+        self.key_names()
+            .iter()
+            .filter(|pk_name| matching_header(headers, pk_name).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn key_names(&self) -> &[String] {
+        match self {
+            PkStrategy::ExistingColumn(pk_name) => std::slice::from_ref(pk_name),
+            PkStrategy::Composite(pk_names) => pk_names,
+            PkStrategy::CreateColumn(_) | PkStrategy::None => &[],
+        }
+    }
+}
+
+/// finds the header a key column name refers to. both sides are compared as the snake_case
+/// field names the generators emit, so `Order ID`, `OrderId` and `order_id` are one column.
+fn matching_header<'h>(headers: &'h StringRecord, pk_name: &str) -> Option<&'h str> {
+    let pk_name = to_snake_case(pk_name);
+    headers
+        .iter()
+        .find(|header| to_snake_case(header) == pk_name)
+}
+
+/// the python version generated code has to run on, which decides how optional types are spelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PythonTarget {
+    /// python 3.10+, optional types are written as `X | None`.
+    #[default]
+    Py310,
+    /// python 3.8/3.9, optional types are written as `Optional[X]` and need a `typing` import.
+    Py38,
+}
+
+impl PythonTarget {
+    /// wraps a python type name in the optional spelling for this target.
+    fn optional(self, py_type: &str) -> String {
+        match self {
+            PythonTarget::Py310 => format!("{} | None", py_type),
+            PythonTarget::Py38 => format!("Optional[{}]", py_type),
+        }
+    }
+}
+
+/// the kind of python class `generate_python` emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PythonFlavor {
+    /// a sqlmodel table class, see `generate_sqlmodel_python`.
+    #[default]
+    SqlModel,
+    /// a pydantic basemodel, see `generate_pydantic_python`.
+    Pydantic,
+    /// a stdlib dataclass, see `generate_dataclass_python`.
+    Dataclass,
+}
+
+/// options for `generate_python`. build with `PythonGenOptions::default()` and the `with_*`
+/// methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PythonGenOptions {
+    /// primary key handling, only used by the sqlmodel flavor.
+    pub pk_strategy: PkStrategy,
+    /// the python version the code has to run on.
+    pub python_target: PythonTarget,
+    /// which kind of class to emit.
+    pub flavor: PythonFlavor,
+    /// per-column nullability for the sqlmodel flavor. `None` treats every column as nullable,
+    /// the pydantic and dataclass flavors always make every field optional.
+    pub nullability: Option<Vec<bool>>,
+    /// snake_case pydantic and dataclass field names word by word instead of lowercasing them,
+    /// see `plain_field_name`. sqlmodel field names are always snake_cased.
+    pub snake_case_fields: bool,
+}
+
+impl Default for PythonGenOptions {
+    fn default() -> Self {
+        Self {
+            pk_strategy: PkStrategy::None,
+            python_target: PythonTarget::default(),
+            flavor: PythonFlavor::default(),
+            nullability: None,
+            snake_case_fields: false,
+        }
+    }
+}
+
+impl PythonGenOptions {
+    /// sets the primary key handling of the sqlmodel flavor.
+    pub fn with_pk_strategy(mut self, pk_strategy: PkStrategy) -> Self {
+        self.pk_strategy = pk_strategy;
+        self
+    }
+
+    /// sets the python version the code has to run on.
+    pub fn with_python_target(mut self, python_target: PythonTarget) -> Self {
+        self.python_target = python_target;
+        self
+    }
+
+    /// sets which kind of class to emit.
+    pub fn with_flavor(mut self, flavor: PythonFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// sets the per-column nullability of the sqlmodel flavor.
+    pub fn with_nullability(mut self, nullability: Vec<bool>) -> Self {
+        self.nullability = Some(nullability);
+        self
+    }
+
+    /// sets whether pydantic and dataclass field names are snake_cased word by word.
+    pub fn with_snake_case_fields(mut self, snake_case_fields: bool) -> Self {
+        self.snake_case_fields = snake_case_fields;
+        self
+    }
+}
+
+/// generates python code of the flavor chosen in `options` from a table name, headers, and
+/// inferred types.
+pub fn generate_python(
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    options: &PythonGenOptions,
+) -> String {
+    write_to_string(|writer| generate_python_to(writer, table_name, headers, types, options))
+}
+
+/// writes the code of `generate_python` to `writer`.
+pub fn generate_python_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    options: &PythonGenOptions,
+) -> io::Result<()> {
+    match options.flavor {
+        PythonFlavor::SqlModel => {
+            let all_nullable;
+            let nullability = match &options.nullability {
+                Some(nullability) => nullability.as_slice(),
+                None => {
+                    all_nullable = vec![true; types.len()];
+                    &all_nullable
+                }
+            };
+            generate_sqlmodel_python_to(
+                writer,
+                table_name,
+                headers,
+                types,
+                nullability,
+                &options.pk_strategy,
+                options.python_target,
+            )
+        }
+        PythonFlavor::Pydantic => write_pydantic(
+            writer,
+            table_name,
+            headers,
+            types,
+            options.snake_case_fields,
+            options.python_target,
+        ),
+        PythonFlavor::Dataclass => write_dataclass(
+            writer,
+            table_name,
+            headers,
+            types,
+            options.snake_case_fields,
+            options.python_target,
+        ),
+    }
+}
+
 /// generates python sqlmodel code from a table name, headers, and inferred types.
+/// columns whose `nullability` entry is false get a non-optional annotation and no default.
 pub fn generate_sqlmodel_python(
     table_name: &str,
     headers: &StringRecord,
     types: &[SqlType],
+    nullability: &[bool],
     pk_strategy: &PkStrategy,
+    python_target: PythonTarget,
 ) -> String {
+    write_to_string(|writer| {
+        generate_sqlmodel_python_to(
+            writer,
+            table_name,
+            headers,
+            types,
+            nullability,
+            pk_strategy,
+            python_target,
+        )
+    })
+}
+
+/// writes the code of `generate_sqlmodel_python` to `writer`.
+pub fn generate_sqlmodel_python_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+    pk_strategy: &PkStrategy,
+    python_target: PythonTarget,
+) -> io::Result<()> {
     let class_name = to_pascal_case(table_name);
 
-    let mut py_code = String::new();
-    // py_code.push_str("from typing import Optional\n"); // no longer needed for `type | none`
-    py_code.push_str("from datetime import date, datetime\n");
-    py_code.push_str("from sqlmodel import Field, SQLModel\n\n\n");
+    writer.write_all(b"from datetime import date, datetime\n")?;
+    if let Some(import) = decimal_import(types) {
+        writer.write_all(import.as_bytes())?;
+    }
+    if python_target == PythonTarget::Py38 {
+        writer.write_all(b"from typing import Optional\n")?;
+    }
+    writer.write_all(b"from sqlmodel import Field, SQLModel\n\n\n")?;
 
-    py_code.push_str(&format!("class {}(SQLModel, table=True):\n", class_name));
+    writeln!(writer, "class {}(SQLModel, table=True):", class_name)?;
 
     let mut pk_field_generated_or_identified = false;
 
-    // handle --pk-create strategy first
-    if let PkStrategy::CreateColumn(pk_name) = pk_strategy {
-        let sanitized_pk_name = pk_name.trim().replace(' ', "_").to_lowercase();
-        py_code.push_str(&format!(
-            "    {}: int = Field(primary_key=True)\n",
-            sanitized_pk_name
-        ));
+    // handle --pk-create strategy first. the name is sanitized once, so the field and the
+    // shadowing check below always agree (`Item ID` and `item_id` are the same field).
+    let created_pk_name = match pk_strategy {
+        PkStrategy::CreateColumn(pk_name) => Some(to_snake_case(pk_name)),
+        _ => None,
+    };
+    if let Some(created_pk_name) = &created_pk_name {
+        writeln!(
+            writer,
+            "    {}: {} = Field(default=None, primary_key=True)",
+            created_pk_name,
+            python_target.optional("int")
+        )?;
         pk_field_generated_or_identified = true;
     }
 
+    // repeated headers (`id, name, id`) would declare the same attribute twice.
+    let field_names = unique_names(headers.iter().map(to_snake_case));
     for (i, header) in headers.iter().enumerate() {
-        let original_header_sanitized = header.trim().replace(' ', "_").to_lowercase();
-
-        // if --pk-create was used, and current header matches the created pk name, skip it
-        if let PkStrategy::CreateColumn(pk_name_to_create) = pk_strategy {
-            if original_header_sanitized
-                == pk_name_to_create.trim().replace(' ', "_").to_lowercase()
-            {
-                // this column from csv is being shadowed by the explicitly created pk.
-                // ideally, we'd warn the user or handle this more gracefully.
-                // for now, we skip generating it from the csv data.
-                continue;
-            }
+        let sql_type = &types[i];
+        let field_name = &field_names[i];
+
+        // a csv column with the created pk's name is replaced by it, say so instead of
+        // dropping the column silently.
+        if created_pk_name.as_ref() == Some(field_name) {
+            writeln!(
+                writer,
+                "    # csv column '{}' is shadowed by the created primary key {}",
+                header, field_name
+            )?;
+            continue;
         }
 
-        let sql_type = &types[i];
-        let field_name = header.trim().replace(' ', "_").to_lowercase();
-        let is_pk = match pk_strategy {
-            PkStrategy::ExistingColumn(pk_name) => {
-                let is_match =
-                    original_header_sanitized == pk_name.trim().replace(' ', "_").to_lowercase();
-                if is_match {
-                    pk_field_generated_or_identified = true;
-                }
-                is_match
+        if pk_strategy.is_key_column(field_name) {
+            pk_field_generated_or_identified = true;
+            // floats compare inexactly, and dates or booleans are rarely unique.
+            if matches!(
+                sql_type,
+                SqlType::Float
+                    | SqlType::Percentage
+                    | SqlType::Date
+                    | SqlType::Datetime
+                    | SqlType::Boolean
+            ) {
+                writeln!(
+                    writer,
+                    "    # warning: {} is an unusual primary key type",
+                    format!("{:?}", sql_type).to_lowercase()
+                )?;
+            }
+            let pk_py_type = python_type_for(sql_type);
+            if pk_py_type == "str" && !nullability[i] {
+                // a text key has no database-generated value to wait for, so it is required.
+                writeln!(writer, "    {}: str = Field(primary_key=True)", field_name)?;
+            } else {
+                // sqlmodel's idiom for integer keys, which the database can generate on insert.
+                writeln!(
+                    writer,
+                    "    {}: {} = Field(default=None, primary_key=True)",
+                    field_name,
+                    python_target.optional(pk_py_type)
+                )?;
             }
-            _ => false,
-        };
-        if is_pk {
-            let pk_py_type = match sql_type {
-                SqlType::Integer | SqlType::BigInt => "int",
-                SqlType::Varchar(_) | SqlType::Char(_) => "str",
-                _ => "int",
-            };
-            py_code.push_str(&format!(
-                "    {}: {} = Field(primary_key=True)\n",
-                field_name, pk_py_type
-            ));
         } else {
-            let (py_type, field_params) = match sql_type {
-                SqlType::Integer | SqlType::BigInt => ("int | None", "default=None".to_string()),
-                SqlType::Float => ("float | None", "default=None".to_string()),
-                SqlType::Char(len) => (
-                    "str | None",
-                    format!("default=None, max_length={}", (*len).max(1)),
-                ),
-                SqlType::Varchar(len) => (
-                    "str | None",
-                    format!("default=None, max_length={}", (*len).max(1)),
-                ),
-                SqlType::Date => ("date | None", "default=None".to_string()),
-                SqlType::Boolean => ("bool | None", "default=None".to_string()),
-                SqlType::Datetime => ("datetime | None", "default=None".to_string()),
+            let py_type = python_type_for(sql_type);
+            let field_params = sqlmodel_field_params(sql_type, nullability[i]);
+            // a column without empty values is required: no optional type and no default.
+            let annotation = if nullability[i] {
+                python_target.optional(py_type)
+            } else {
+                py_type.to_string()
             };
-            py_code.push_str(&format!(
-                "    {}: {} = Field({})\n",
-                field_name, py_type, field_params
-            ));
+            if field_params.is_empty() {
+                writeln!(writer, "    {}: {}", field_name, annotation)?;
+            } else {
+                writeln!(
+                    writer,
+                    "    {}: {} = Field({})",
+                    field_name,
+                    annotation,
+                    field_params.join(", ")
+                )?;
+            }
         }
     }
 
-    if !pk_field_generated_or_identified && !headers.is_empty() {
+    let missing_pk_columns = pk_strategy.missing_columns(headers);
+    if !missing_pk_columns.is_empty() {
+        // most likely a typo in the column name, so make it stand out.
+        for pk_name in missing_pk_columns {
+            writeln!(
+                writer,
+                "    # error: pk column '{}' not found in csv headers",
+                pk_name
+            )?;
+        }
+    } else if !pk_field_generated_or_identified && !headers.is_empty() {
         // this condition means headers were present, fields were generated, but no pk was made.
-        py_code
-            .push_str("    # todo: review and define a primary_key=true field for this model.\n");
+        writer.write_all(
+            b"    # todo: review and define a primary_key=true field for this model.\n",
+        )?;
     } else if headers.is_empty() && !pk_field_generated_or_identified {
-        py_code.push_str("    # no columns inferred, add fields manually\n    pass\n");
+        writer.write_all(b"    # no columns inferred, add fields manually\n    pass\n")?;
     } else if headers.is_empty() && matches!(pk_strategy, PkStrategy::CreateColumn(_)) {
         // only the --pk-create field was generated
-        py_code.push_str("    pass # only primary key field was generated, add other fields\n");
+        writer.write_all(b"    pass # only primary key field was generated, add other fields\n")?;
+    }
+
+    Ok(())
+}
+
+/// returns the python type of a column of `sql_type`, as used by every python generator:
+/// `int`, `float`, `Decimal`, `str`, `date`, `datetime` or `bool`. fixed-point columns (money)
+/// are `decimal.Decimal`, since a float would lose their exact cents.
+pub fn python_type_for(sql_type: &SqlType) -> &'static str {
+    match sql_type {
+        SqlType::Integer | SqlType::BigInt => "int",
+        SqlType::Float | SqlType::Percentage => "float",
+        SqlType::Decimal(..) => "Decimal",
+        SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet | SqlType::Json => {
+            "str"
+        }
+        SqlType::Date => "date",
+        SqlType::Boolean => "bool",
+        SqlType::Datetime => "datetime",
+    }
+}
+
+/// returns the `max_length` a sqlmodel `Field` validates for a column of `sql_type`, or `None`
+/// for unbounded and non-text types.
+fn python_max_length(sql_type: &SqlType) -> Option<usize> {
+    match sql_type {
+        SqlType::Char(len) | SqlType::Varchar(len) => Some((*len).max(1)),
+        SqlType::Inet => Some(45),
+        // unbounded, so there is no max_length to validate against.
+        _ => None,
+    }
+}
+
+/// returns the arguments of the sqlmodel `Field(...)` of a non-key column, e.g.
+/// `["default=None", "max_length=20"]`. a decimal column gets `max_digits` and
+/// `decimal_places`, from which sqlmodel creates a `NUMERIC(p, s)` column. empty if the column
+/// needs no `Field` at all.
+pub fn sqlmodel_field_params(sql_type: &SqlType, nullable: bool) -> Vec<String> {
+    let mut params = Vec::new();
+    if nullable {
+        params.push("default=None".to_string());
+    }
+    if let Some(len) = python_max_length(sql_type) {
+        params.push(format!("max_length={}", len));
+    }
+    if let SqlType::Decimal(precision, scale) = sql_type {
+        params.push(format!("max_digits={}", precision.max(&1)));
+        params.push(format!("decimal_places={}", scale));
+    }
+    params
+}
+
+/// returns the `from decimal import Decimal` line if any of `types` is a decimal.
+fn decimal_import(types: &[SqlType]) -> Option<&'static str> {
+    types
+        .iter()
+        .any(|t| matches!(t, SqlType::Decimal(..)))
+        .then_some("from decimal import Decimal\n")
+}
+
+/// returns the `from datetime import ...` line covering only the names used by `types`.
+fn datetime_import(types: &[SqlType]) -> Option<&'static str> {
+    let needs_date = types.iter().any(|t| matches!(t, SqlType::Date));
+    let needs_datetime = types.iter().any(|t| matches!(t, SqlType::Datetime));
+    match (needs_date, needs_datetime) {
+        (true, true) => Some("from datetime import date, datetime\n"),
+        (true, false) => Some("from datetime import date\n"),
+        (false, true) => Some("from datetime import datetime\n"),
+        (false, false) => None,
+    }
+}
+
+/// returns the field name of the pydantic and dataclass generators for `header`: lowercased
+/// with spaces as underscores, or with `snake_case_fields` snake_cased word by word, so that
+/// `OrderID` becomes `order_id` rather than `orderid`.
+fn plain_field_name(header: &str, snake_case_fields: bool) -> String {
+    if snake_case_fields {
+        to_snake_case(header)
+    } else {
+        header.trim().replace(' ', "_").to_lowercase()
     }
+}
+
+/// generates a pydantic (v2) basemodel from a model name, headers, and inferred types.
+/// unlike the sqlmodel output, no table or primary key scaffolding is emitted.
+pub fn generate_pydantic_python(
+    model_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+) -> String {
+    write_to_string(|writer| generate_pydantic_python_to(writer, model_name, headers, types))
+}
 
-    py_code
+/// writes the code of `generate_pydantic_python` to `writer`.
+pub fn generate_pydantic_python_to<W: Write>(
+    writer: &mut W,
+    model_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+) -> io::Result<()> {
+    write_pydantic(
+        writer,
+        model_name,
+        headers,
+        types,
+        false,
+        PythonTarget::Py310,
+    )
+}
+
+fn write_pydantic<W: Write>(
+    writer: &mut W,
+    model_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    snake_case_fields: bool,
+    python_target: PythonTarget,
+) -> io::Result<()> {
+    let class_name = to_pascal_case(model_name);
+
+    if let Some(import) = datetime_import(types) {
+        writer.write_all(import.as_bytes())?;
+    }
+    if let Some(import) = decimal_import(types) {
+        writer.write_all(import.as_bytes())?;
+    }
+    if python_target == PythonTarget::Py38 {
+        writer.write_all(b"from typing import Optional\n")?;
+    }
+    writer.write_all(b"from pydantic import BaseModel\n\n\n")?;
+
+    writeln!(writer, "class {}(BaseModel):", class_name)?;
+
+    // repeated headers (`id, Name, id, name`) would declare the same attribute twice.
+    let field_names = unique_names(
+        headers
+            .iter()
+            .map(|header| plain_field_name(header, snake_case_fields)),
+    );
+    for (field_name, sql_type) in field_names.iter().zip(types.iter()) {
+        writeln!(
+            writer,
+            "    {}: {} = None",
+            field_name,
+            python_target.optional(python_type_for(sql_type))
+        )?;
+    }
+
+    if headers.is_empty() {
+        writer.write_all(b"    # no columns inferred, add fields manually\n    pass\n")?;
+    }
+
+    Ok(())
+}
+
+/// generates a stdlib python `@dataclass` from a class name, headers, and inferred types.
+/// every field defaults to `None`, so no orm or validation library is required. field names
+/// are made like those of `generate_pydantic_python`.
+pub fn generate_dataclass_python(
+    class_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+) -> String {
+    write_to_string(|writer| generate_dataclass_python_to(writer, class_name, headers, types))
+}
+
+/// writes the code of `generate_dataclass_python` to `writer`.
+pub fn generate_dataclass_python_to<W: Write>(
+    writer: &mut W,
+    class_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+) -> io::Result<()> {
+    write_dataclass(
+        writer,
+        class_name,
+        headers,
+        types,
+        false,
+        PythonTarget::Py310,
+    )
+}
+
+fn write_dataclass<W: Write>(
+    writer: &mut W,
+    class_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    snake_case_fields: bool,
+    python_target: PythonTarget,
+) -> io::Result<()> {
+    let class_name = to_pascal_case(class_name);
+
+    writer.write_all(b"from dataclasses import dataclass\n")?;
+    if let Some(import) = datetime_import(types) {
+        writer.write_all(import.as_bytes())?;
+    }
+    if let Some(import) = decimal_import(types) {
+        writer.write_all(import.as_bytes())?;
+    }
+    if python_target == PythonTarget::Py38 {
+        writer.write_all(b"from typing import Optional\n")?;
+    }
+    writer.write_all(b"\n\n@dataclass\n")?;
+    writeln!(writer, "class {}:", class_name)?;
+
+    // all fields carry a default, so csv column order never violates the
+    // "non-default fields before default fields" rule of dataclasses.
+    let field_names = unique_names(
+        headers
+            .iter()
+            .map(|header| plain_field_name(header, snake_case_fields)),
+    );
+    for (field_name, sql_type) in field_names.iter().zip(types.iter()) {
+        writeln!(
+            writer,
+            "    {}: {} = None",
+            field_name,
+            python_target.optional(python_type_for(sql_type))
+        )?;
+    }
+
+    if headers.is_empty() {
+        writer.write_all(b"    # no columns inferred, add fields manually\n    pass\n")?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -117,6 +617,91 @@ mod tests {
     use crate::SqlType; // ensure sqltype is in scope
     use csv::StringRecord;
 
+    #[test]
+    fn test_python_type_for_every_variant() {
+        let cases = [
+            (SqlType::Integer, "int"),
+            (SqlType::BigInt, "int"),
+            (SqlType::Float, "float"),
+            (SqlType::Decimal(10, 2), "Decimal"),
+            (SqlType::Percentage, "float"),
+            (SqlType::Char(3), "str"),
+            (SqlType::Varchar(20), "str"),
+            (SqlType::Text, "str"),
+            (SqlType::Inet, "str"),
+            (SqlType::Json, "str"),
+            (SqlType::Date, "date"),
+            (SqlType::Datetime, "datetime"),
+            (SqlType::Boolean, "bool"),
+        ];
+        for (sql_type, expected) in cases {
+            assert_eq!(python_type_for(&sql_type), expected, "{:?}", sql_type);
+        }
+    }
+
+    #[test]
+    fn test_sqlmodel_field_params() {
+        assert_eq!(
+            sqlmodel_field_params(&SqlType::Varchar(20), true),
+            ["default=None", "max_length=20"]
+        );
+        assert_eq!(
+            sqlmodel_field_params(&SqlType::Char(0), false),
+            ["max_length=1"]
+        );
+        assert_eq!(
+            sqlmodel_field_params(&SqlType::Integer, true),
+            ["default=None"]
+        );
+        assert_eq!(
+            sqlmodel_field_params(&SqlType::Decimal(8, 2), false),
+            ["max_digits=8", "decimal_places=2"]
+        );
+        assert!(sqlmodel_field_params(&SqlType::Text, false).is_empty());
+    }
+
+    #[test]
+    fn test_generate_model_money_column_is_decimal() {
+        let headers = StringRecord::from(vec!["item", "price", "discount"]);
+        let types = vec![
+            SqlType::Varchar(20),
+            SqlType::Decimal(8, 2),
+            SqlType::Decimal(4, 2),
+        ];
+        let generated_python = generate_sqlmodel_python(
+            "products",
+            &headers,
+            &types,
+            &[false, false, true],
+            &PkStrategy::CreateColumn("id".to_string()),
+            PythonTarget::Py310,
+        );
+        assert!(generated_python.starts_with(
+            "from datetime import date, datetime\nfrom decimal import Decimal\nfrom sqlmodel"
+        ));
+        assert!(
+            generated_python
+                .contains("    price: Decimal = Field(max_digits=8, decimal_places=2)\n")
+        );
+        assert!(generated_python.contains(
+            "    discount: Decimal | None = Field(default=None, max_digits=4, decimal_places=2)\n"
+        ));
+
+        let without_money = generate_sqlmodel_python(
+            "products",
+            &StringRecord::from(vec!["item"]),
+            &types[..1],
+            &[false],
+            &PkStrategy::None,
+            PythonTarget::Py310,
+        );
+        assert!(!without_money.contains("decimal"));
+
+        let dataclass = generate_dataclass_python("products", &headers, &types);
+        assert!(dataclass.contains("from decimal import Decimal\n"));
+        assert!(dataclass.contains("    price: Decimal | None = None\n"));
+    }
+
     fn normalize_whitespace(s: &str) -> String {
         s.lines()
             .map(|line| line.trim())
@@ -140,8 +725,14 @@ class SimpleUsers(SQLModel, table=True):
     age: int | None = Field(default=None)
     # todo: review and define a primary_key=true field for this model.
 "#;
-        let generated_python =
-            generate_sqlmodel_python(table_name, &headers, &types, &PkStrategy::None);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
         assert_eq!(
             normalize_whitespace(&generated_python),
             normalize_whitespace(expected_python)
@@ -167,7 +758,37 @@ class ProductsTable(SQLModel, table=True):
             table_name,
             &headers,
             &types,
+            &vec![true; types.len()],
             &PkStrategy::ExistingColumn("id".to_string()),
+            PythonTarget::default(),
+        );
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+    }
+
+    #[test]
+    fn test_generate_model_snake_cases_field_names() {
+        let headers = StringRecord::from(vec!["userId", "First Name", "e.mail"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20), SqlType::Varchar(40)];
+        let expected_python = r#"
+from datetime import date, datetime
+from sqlmodel import Field, SQLModel
+
+
+class Users(SQLModel, table=True):
+    user_id: int | None = Field(default=None, primary_key=True)
+    first_name: str | None = Field(default=None, max_length=20)
+    e_mail: str | None = Field(default=None, max_length=40)
+"#;
+        let generated_python = generate_sqlmodel_python(
+            "users",
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &PkStrategy::ExistingColumn("user_id".to_string()),
+            PythonTarget::default(),
         );
         assert_eq!(
             normalize_whitespace(&generated_python),
@@ -175,6 +796,205 @@ class ProductsTable(SQLModel, table=True):
         );
     }
 
+    #[test]
+    fn test_generate_model_warns_about_float_pk() {
+        let headers = StringRecord::from(vec!["score", "id"]);
+        let types = vec![SqlType::Float, SqlType::Integer];
+        let generate = |pk: &str| {
+            generate_sqlmodel_python(
+                "scores",
+                &headers,
+                &types,
+                &vec![false; types.len()],
+                &PkStrategy::ExistingColumn(pk.to_string()),
+                PythonTarget::default(),
+            )
+        };
+
+        let float_pk = generate("score");
+        let lines: Vec<&str> = float_pk.lines().collect();
+        let warning = lines
+            .iter()
+            .position(|line| *line == "    # warning: float is an unusual primary key type")
+            .expect("missing warning");
+        assert_eq!(
+            lines[warning + 1],
+            "    score: float | None = Field(default=None, primary_key=True)"
+        );
+        assert!(!generate("id").contains("# warning"));
+
+        let headers = StringRecord::from(vec!["day", "price"]);
+        let types = vec![SqlType::Date, SqlType::Decimal(6, 2)];
+        let generate = |pk: &str| {
+            generate_sqlmodel_python(
+                "prices",
+                &headers,
+                &types,
+                &vec![true; types.len()],
+                &PkStrategy::ExistingColumn(pk.to_string()),
+                PythonTarget::default(),
+            )
+        };
+        assert!(
+            generate("day")
+                .contains("    day: date | None = Field(default=None, primary_key=True)\n")
+        );
+        assert!(
+            generate("price")
+                .contains("    price: Decimal | None = Field(default=None, primary_key=True)\n")
+        );
+    }
+
+    #[test]
+    fn test_generate_model_reports_missing_pk_column() {
+        let headers = StringRecord::from(vec!["id", "name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(10)];
+        let pk_strategy = PkStrategy::ExistingColumn("uuid".to_string());
+        let generated_python = generate_sqlmodel_python(
+            "users",
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &pk_strategy,
+            PythonTarget::default(),
+        );
+        assert!(
+            generated_python.contains("    # error: pk column 'uuid' not found in csv headers")
+        );
+        assert!(!generated_python.contains("# todo"));
+
+        let err = pk_strategy.validate(&headers).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(
+            PkStrategy::ExistingColumn("ID".to_string())
+                .validate(&headers)
+                .is_ok()
+        );
+        assert!(PkStrategy::None.validate(&headers).is_ok());
+    }
+
+    #[test]
+    fn test_pk_column_matches_sanitized_header() {
+        let headers = StringRecord::from(vec!["order_id", "Customer Name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20)];
+        let pk_strategy = PkStrategy::ExistingColumn("Order ID".to_string());
+        assert!(pk_strategy.validate(&headers).is_ok());
+        assert_eq!(pk_strategy.key_headers(&headers), vec!["order_id"]);
+        let generated_python = generate_sqlmodel_python(
+            "orders",
+            &headers,
+            &types,
+            &[true, true],
+            &pk_strategy,
+            PythonTarget::default(),
+        );
+        assert!(
+            generated_python
+                .contains("    order_id: int | None = Field(default=None, primary_key=True)\n")
+        );
+        assert!(!generated_python.contains("# todo"));
+
+        let composite =
+            PkStrategy::Composite(vec!["ORDER_ID".to_string(), "customerName".to_string()]);
+        assert_eq!(
+            composite.key_headers(&headers),
+            vec!["order_id", "Customer Name"]
+        );
+    }
+
+    #[test]
+    fn test_generate_model_with_composite_pk() {
+        let headers = StringRecord::from(vec!["student_id", "course_id", "grade"]);
+        let types = vec![SqlType::Integer, SqlType::Integer, SqlType::Char(1)];
+        let expected_python = r#"
+from datetime import date, datetime
+from sqlmodel import Field, SQLModel
+
+
+class Enrollments(SQLModel, table=True):
+    student_id: int | None = Field(default=None, primary_key=True)
+    course_id: int | None = Field(default=None, primary_key=True)
+    grade: str | None = Field(default=None, max_length=1)
+"#;
+        let pk_strategy =
+            PkStrategy::Composite(vec!["student_id".to_string(), "course_id".to_string()]);
+        let generated_python = generate_sqlmodel_python(
+            "enrollments",
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &pk_strategy,
+            PythonTarget::default(),
+        );
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+
+        assert!(pk_strategy.validate(&headers).is_ok());
+        let err = PkStrategy::Composite(vec!["student_id".to_string(), "term".to_string()])
+            .validate(&headers)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "pk column 'term' not found in csv headers");
+    }
+
+    #[test]
+    fn test_generate_model_class_name_from_unsafe_table_name() {
+        let headers = StringRecord::from(vec!["id"]);
+        let types = vec![SqlType::Integer];
+        let generate = |table_name: &str| {
+            generate_sqlmodel_python(
+                table_name,
+                &headers,
+                &types,
+                &[false],
+                &PkStrategy::None,
+                PythonTarget::default(),
+            )
+        };
+        assert!(generate("2023 sales!").contains("class T2023Sales(SQLModel, table=True):"));
+        assert!(generate("123").contains("class T123(SQLModel, table=True):"));
+    }
+
+    #[test]
+    fn test_generate_model_disambiguates_duplicate_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "ID"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(10), SqlType::Integer];
+        let generated_python = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &[false, false, false],
+            &PkStrategy::ExistingColumn("id".to_string()),
+            PythonTarget::default(),
+        );
+        assert!(
+            generated_python
+                .contains("    id: int | None = Field(default=None, primary_key=True)\n")
+        );
+        assert!(generated_python.contains("    name: str = Field(max_length=10)\n"));
+        assert!(generated_python.contains("    id_2: int\n"));
+    }
+
+    #[test]
+    fn test_generate_model_text_column_has_no_max_length() {
+        let headers = StringRecord::from(vec!["title", "body"]);
+        let types = vec![SqlType::Varchar(80), SqlType::Text];
+        let generated_python = generate_sqlmodel_python(
+            "posts",
+            &headers,
+            &types,
+            &[true, true],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
+        assert!(
+            generated_python
+                .contains("    title: str | None = Field(default=None, max_length=80)\n")
+        );
+        assert!(generated_python.contains("    body: str | None = Field(default=None)\n"));
+    }
+
     #[test]
     fn test_generate_model_with_pk_create() {
         let table_name = "items";
@@ -191,7 +1011,14 @@ class Items(SQLModel, table=True):
     item_name: str | None = Field(default=None, max_length=50)
     quantity: int | None = Field(default=None)
 "#;
-        let generated_python = generate_sqlmodel_python(table_name, &headers, &types, &pk_strategy);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &pk_strategy,
+            PythonTarget::default(),
+        );
         assert_eq!(
             normalize_whitespace(&generated_python),
             normalize_whitespace(expected_python)
@@ -216,24 +1043,64 @@ from sqlmodel import Field, SQLModel
 
 class Events(SQLModel, table=True):
     event_id: int | None = Field(default=None, primary_key=True)
+    # csv column 'event_id' is shadowed by the created primary key event_id
     event_name: str | None = Field(default=None, max_length=50)
     location: str | None = Field(default=None, max_length=30)
 "#;
         // the event_id from csv (varchar(10)) should be skipped in favor of the created int pk.
-        let generated_python = generate_sqlmodel_python(table_name, &headers, &types, &pk_strategy);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &pk_strategy,
+            PythonTarget::default(),
+        );
         assert_eq!(
             normalize_whitespace(&generated_python),
             normalize_whitespace(expected_python)
         );
     }
 
+    #[test]
+    fn test_generate_model_with_pk_create_shadows_after_sanitizing() {
+        let headers = StringRecord::from(vec!["item_id", "item_name"]);
+        let types = vec![SqlType::Varchar(10), SqlType::Varchar(50)];
+        let generated_python = generate_sqlmodel_python(
+            "items",
+            &headers,
+            &types,
+            &[true, true],
+            &PkStrategy::CreateColumn("Item ID".to_string()),
+            PythonTarget::default(),
+        );
+        let field_lines: Vec<&str> = generated_python
+            .lines()
+            .filter(|line| line.starts_with("    "))
+            .collect();
+        assert_eq!(
+            field_lines,
+            vec![
+                "    item_id: int | None = Field(default=None, primary_key=True)",
+                "    # csv column 'item_id' is shadowed by the created primary key item_id",
+                "    item_name: str | None = Field(default=None, max_length=50)",
+            ]
+        );
+    }
+
     #[test]
     fn test_generate_model_no_pk_strategy_adds_comment() {
         let table_name = "logs";
         let headers = StringRecord::from(vec!["message", "level"]);
         let types = vec![SqlType::Varchar(200), SqlType::Char(5)];
-        let generated_python =
-            generate_sqlmodel_python(table_name, &headers, &types, &PkStrategy::None);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
         assert!(
             generated_python
                 .contains("# todo: review and define a primary_key=true field for this model.")
@@ -275,8 +1142,14 @@ class ComprehensiveData(SQLModel, table=True):
     short_code: str | None = Field(default=None, max_length=10)
     # todo: review and define a primary_key=true field for this model.
 "#;
-        let generated_python =
-            generate_sqlmodel_python(table_name, &headers, &types, &PkStrategy::None);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
         assert_eq!(
             normalize_whitespace(&generated_python),
             normalize_whitespace(expected_python)
@@ -297,14 +1170,326 @@ class EmptyTable(SQLModel, table=True):
     # no columns inferred, add fields manually
     pass
 "#;
-        let generated_python =
-            generate_sqlmodel_python(table_name, &headers, &types, &PkStrategy::None);
+        let generated_python = generate_sqlmodel_python(
+            table_name,
+            &headers,
+            &types,
+            &vec![true; types.len()],
+            &PkStrategy::None,
+            PythonTarget::default(),
+        );
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+    }
+
+    #[test]
+    fn test_generate_model_optional_style_for_py38() {
+        let headers = StringRecord::from(vec!["visits", "last_seen"]);
+        let types = vec![SqlType::Integer, SqlType::Datetime];
+        let expected_python = r#"
+from datetime import date, datetime
+from typing import Optional
+from sqlmodel import Field, SQLModel
+
+
+class Visitors(SQLModel, table=True):
+    visits: Optional[int] = Field(default=None)
+    last_seen: Optional[datetime] = Field(default=None)
+    # todo: review and define a primary_key=true field for this model.
+"#;
+        let generated_python = generate_sqlmodel_python(
+            "visitors",
+            &headers,
+            &types,
+            &[true, true],
+            &PkStrategy::None,
+            PythonTarget::Py38,
+        );
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+    }
+
+    #[test]
+    fn test_generate_model_union_style_for_py310() {
+        let headers = StringRecord::from(vec!["visits", "last_seen"]);
+        let types = vec![SqlType::Integer, SqlType::Datetime];
+        let generated_python = generate_sqlmodel_python(
+            "visitors",
+            &headers,
+            &types,
+            &[true, true],
+            &PkStrategy::None,
+            PythonTarget::Py310,
+        );
+        assert!(generated_python.contains("    visits: int | None = Field(default=None)\n"));
+        assert!(
+            generated_python.contains("    last_seen: datetime | None = Field(default=None)\n")
+        );
+        assert!(!generated_python.contains("Optional"));
+        assert!(!generated_python.contains("from typing"));
+    }
+
+    #[test]
+    fn test_generate_model_non_nullable_fields() {
+        let headers = StringRecord::from(vec!["id", "name", "age", "nickname"]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::Varchar(50),
+            SqlType::Integer,
+            SqlType::Varchar(20),
+        ];
+        // id, name and age never had empty cells, nickname did.
+        let nullability = vec![false, false, false, true];
+        let expected_python = r#"
+from datetime import date, datetime
+from sqlmodel import Field, SQLModel
+
+
+class People(SQLModel, table=True):
+    person_id: int | None = Field(default=None, primary_key=True)
+    id: int
+    name: str = Field(max_length=50)
+    age: int
+    nickname: str | None = Field(default=None, max_length=20)
+"#;
+        let generated_python = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &nullability,
+            &PkStrategy::CreateColumn("person_id".to_string()),
+            PythonTarget::default(),
+        );
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+    }
+
+    #[test]
+    fn test_generate_model_partially_empty_column_stays_optional() {
+        let headers = StringRecord::from(vec!["age"]);
+        let types = vec![SqlType::Integer];
+        let populated = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &[false],
+            &PkStrategy::None,
+            PythonTarget::Py38,
+        );
+        let partially_empty = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &[true],
+            &PkStrategy::None,
+            PythonTarget::Py38,
+        );
+        assert!(populated.contains("    age: int\n"));
+        assert!(partially_empty.contains("    age: Optional[int] = Field(default=None)\n"));
+    }
+
+    #[test]
+    fn test_generate_model_required_and_optional_fields() {
+        let headers = StringRecord::from(vec!["code", "name", "nickname", "born"]);
+        let types = vec![
+            SqlType::Char(3),
+            SqlType::Varchar(50),
+            SqlType::Varchar(20),
+            SqlType::Date,
+        ];
+        let generated_python = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &[false, false, true, false],
+            &PkStrategy::ExistingColumn("code".to_string()),
+            PythonTarget::Py310,
+        );
+        let expected_python = r#"
+class People(SQLModel, table=True):
+    code: str = Field(primary_key=True)
+    name: str = Field(max_length=50)
+    nickname: str | None = Field(default=None, max_length=20)
+    born: date
+"#;
+        assert!(generated_python.ends_with(expected_python.trim_start_matches('\n')));
+
+        let created_pk = generate_sqlmodel_python(
+            "people",
+            &headers,
+            &types,
+            &[true, false, true, false],
+            &PkStrategy::CreateColumn("id".to_string()),
+            PythonTarget::Py310,
+        );
+        assert!(
+            created_pk.contains("    id: int | None = Field(default=None, primary_key=True)\n")
+        );
+        assert!(created_pk.contains("    code: str | None = Field(default=None, max_length=3)\n"));
+        assert!(created_pk.contains("    name: str = Field(max_length=50)\n"));
+    }
+
+    #[test]
+    fn test_generate_pydantic_model() {
+        let headers = StringRecord::from(vec!["id", "full name", "signup_date", "is_active"]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::Varchar(30),
+            SqlType::Date,
+            SqlType::Boolean,
+        ];
+        let expected_python = r#"
+from datetime import date
+from pydantic import BaseModel
+
+
+class UserPayload(BaseModel):
+    id: int | None = None
+    full_name: str | None = None
+    signup_date: date | None = None
+    is_active: bool | None = None
+"#;
+        let generated_python = generate_pydantic_python("user_payload", &headers, &types);
+        assert_eq!(
+            normalize_whitespace(&generated_python),
+            normalize_whitespace(expected_python)
+        );
+    }
+
+    #[test]
+    fn test_generate_pydantic_model_has_no_pk_fields() {
+        let headers = StringRecord::from(vec!["id", "created_at", "score"]);
+        let types = vec![SqlType::Integer, SqlType::Datetime, SqlType::Float];
+        let generated_python = generate_pydantic_python("events", &headers, &types);
+
+        assert!(generated_python.contains("from datetime import datetime\n"));
+        assert!(!generated_python.contains("Field("));
+        assert!(!generated_python.contains("primary_key"));
+        assert!(!generated_python.contains("table=True"));
+        assert!(!generated_python.contains("sqlmodel"));
+    }
+
+    #[test]
+    fn test_generate_dataclass_model() {
+        let headers = StringRecord::from(vec!["order_id", "placed_at", "ship_date", "total"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Datetime,
+            SqlType::Date,
+            SqlType::Float,
+        ];
+        let expected_python = r#"
+from dataclasses import dataclass
+from datetime import date, datetime
+
+
+@dataclass
+class Orders:
+    order_id: int | None = None
+    placed_at: datetime | None = None
+    ship_date: date | None = None
+    total: float | None = None
+"#;
+        let generated_python = generate_dataclass_python("orders", &headers, &types);
         assert_eq!(
             normalize_whitespace(&generated_python),
             normalize_whitespace(expected_python)
         );
     }
 
+    #[test]
+    fn test_plain_field_names_of_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "Name", "id", "name"]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::Text,
+            SqlType::Integer,
+            SqlType::Text,
+        ];
+        for generated_python in [
+            generate_pydantic_python("people", &headers, &types),
+            generate_dataclass_python("people", &headers, &types),
+        ] {
+            assert!(generated_python.contains("    id: int | None = None\n"));
+            assert!(generated_python.contains("    name: str | None = None\n"));
+            assert!(generated_python.contains("    id_2: int | None = None\n"));
+            assert!(generated_python.contains("    name_2: str | None = None\n"));
+        }
+    }
+
+    #[test]
+    fn test_snake_case_field_names() {
+        let headers = StringRecord::from(vec!["OrderID", "Customer Name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20)];
+        let lowercased = generate_dataclass_python("orders", &headers, &types);
+        assert!(lowercased.contains("    orderid: int | None = None\n"));
+        assert!(lowercased.contains("    customer_name: str | None = None\n"));
+        let options = PythonGenOptions::default()
+            .with_flavor(PythonFlavor::Pydantic)
+            .with_snake_case_fields(true);
+        let snake_cased = generate_python("orders", &headers, &types, &options);
+        assert!(snake_cased.contains("    order_id: int | None = None\n"));
+        assert!(snake_cased.contains("    customer_name: str | None = None\n"));
+    }
+
+    #[test]
+    fn test_generate_dataclass_all_fields_have_defaults() {
+        let headers = StringRecord::from(vec!["name", "age", "is_admin"]);
+        let types = vec![SqlType::Varchar(10), SqlType::Integer, SqlType::Boolean];
+        let generated_python = generate_dataclass_python("people", &headers, &types);
+
+        let field_lines: Vec<&str> = generated_python
+            .lines()
+            .filter(|line| line.starts_with("    "))
+            .collect();
+        assert_eq!(field_lines.len(), 3);
+        assert!(field_lines.iter().all(|line| line.ends_with(" = None")));
+        // no date/datetime fields, so the datetime import is omitted entirely
+        assert!(!generated_python.contains("from datetime"));
+        assert!(!generated_python.contains("pydantic"));
+        assert!(!generated_python.contains("sqlmodel"));
+    }
+
+    #[test]
+    fn test_generate_python_options_select_optional_style() {
+        let headers = StringRecord::from(vec!["id", "name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20)];
+        let options = PythonGenOptions::default()
+            .with_pk_strategy(PkStrategy::ExistingColumn("id".to_string()))
+            .with_python_target(PythonTarget::Py38)
+            .with_nullability(vec![false, true]);
+        let generated_python = generate_python("people", &headers, &types, &options);
+        assert_eq!(
+            generated_python,
+            generate_sqlmodel_python(
+                "people",
+                &headers,
+                &types,
+                &[false, true],
+                &options.pk_strategy,
+                PythonTarget::Py38,
+            )
+        );
+        assert!(generated_python.contains("from typing import Optional\n"));
+        assert!(generated_python.contains("    name: Optional[str] = Field("));
+
+        let pydantic = generate_python(
+            "people",
+            &headers,
+            &types,
+            &options.clone().with_flavor(PythonFlavor::Pydantic),
+        );
+        assert!(pydantic.contains("from typing import Optional\n"));
+        assert!(pydantic.contains("    id: Optional[int] = None\n"));
+        assert!(!pydantic.contains(" | None"));
+    }
+
     // test for a table name that needs pascal case conversion is implicitly covered
     // by other tests like test_generate_simple_model (simple_users -> SimpleUsers)
     // and test_generate_model_with_id_pk (products_table -> ProductsTable).
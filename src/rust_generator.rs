@@ -0,0 +1,143 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{to_pascal_case, to_snake_case, unique_names, write_to_string};
+
+/// rust keywords that need the raw identifier prefix (`r#`) to be used as field names.
+pub(crate) const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+/// turns a csv header into a snake_case rust field name (see `to_snake_case`). a leading digit
+/// gets an underscore prefix and keywords become raw identifiers.
+fn rust_field_name(header: &str) -> String {
+    let name = to_snake_case(header);
+
+    if name.is_empty() {
+        "_".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// generates a rust struct with serde derives from a struct name, headers, and inferred types.
+/// nullable columns are wrapped in `Option<T>`, and a `#[serde(rename = ...)]` attribute keeps
+/// the original header whenever the field name had to be changed.
+pub fn generate_rust_struct(
+    struct_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| {
+        generate_rust_struct_to(writer, struct_name, headers, types, nullability)
+    })
+}
+
+/// writes the struct of `generate_rust_struct` to `writer`.
+pub fn generate_rust_struct_to<W: Write>(
+    writer: &mut W,
+    struct_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writer.write_all(b"use serde::{Deserialize, Serialize};\n\n")?;
+    writer.write_all(b"#[derive(Debug, Deserialize, Serialize)]\n")?;
+    writeln!(writer, "pub struct {} {{", to_pascal_case(struct_name))?;
+
+    // repeated headers (`id, name, id`) would declare the same field twice.
+    let field_names = unique_names(headers.iter().map(rust_field_name));
+    for (i, header) in headers.iter().enumerate() {
+        // dates and datetimes are kept as their textual representation.
+        let rust_type = match &types[i] {
+            SqlType::Integer => "i32",
+            SqlType::BigInt => "i64",
+            SqlType::Float | SqlType::Decimal(..) | SqlType::Percentage => "f64",
+            SqlType::Boolean => "bool",
+            SqlType::Date | SqlType::Datetime => "String",
+            SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet => "String",
+            SqlType::Json => "serde_json::Value",
+        };
+        let field_type = if nullability[i] {
+            format!("Option<{}>", rust_type)
+        } else {
+            rust_type.to_string()
+        };
+
+        let field_name = &field_names[i];
+        if field_name.trim_start_matches("r#") != header {
+            writeln!(writer, "    #[serde(rename = {:?})]", header)?;
+        }
+        writeln!(writer, "    pub {}: {},", field_name, field_type)?;
+    }
+
+    writer.write_all(b"}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_rust_struct() {
+        let headers = StringRecord::from(vec!["id", "Order Total", "placed_on", "is_paid"]);
+        let types = vec![
+            SqlType::BigInt,
+            SqlType::Float,
+            SqlType::Date,
+            SqlType::Boolean,
+        ];
+        let nullability = vec![false, true, false, true];
+        let expected = r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Orders {
+    pub id: i64,
+    #[serde(rename = "Order Total")]
+    pub order_total: Option<f64>,
+    pub placed_on: String,
+    pub is_paid: Option<bool>,
+}"#;
+        assert_eq!(
+            generate_rust_struct("orders", &headers, &types, &nullability),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_rename_only_when_name_changes() {
+        let headers = StringRecord::from(vec!["first name", "age", "type", "2nd"]);
+        let types = vec![
+            SqlType::Varchar(10),
+            SqlType::Integer,
+            SqlType::Char(1),
+            SqlType::Integer,
+        ];
+        let generated = generate_rust_struct("people", &headers, &types, &[true; 4]);
+
+        assert!(generated.contains("    #[serde(rename = \"first name\")]\n    pub first_name:"));
+        assert!(!generated.contains("rename = \"age\""));
+        // keywords become raw identifiers, which serde already maps back to `type`.
+        assert!(generated.contains("    pub r#type: Option<String>,"));
+        assert!(!generated.contains("rename = \"type\""));
+        assert!(generated.contains("    #[serde(rename = \"2nd\")]\n    pub _2nd: Option<i32>,"));
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        let generated = generate_rust_struct("people", &headers, &types, &[false; 3]);
+        assert!(generated.contains("    pub id: i32,\n"));
+        assert!(generated.contains("    #[serde(rename = \"id\")]\n    pub id_2: i32,\n"));
+    }
+}
@@ -1,31 +1,1084 @@
-use super::{SqlType, StringRecord};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use super::{ColumnStats, SqlType, StringRecord};
+use crate::utils::{to_snake_case, unique_names, write_to_string};
+
+/// the sql dialect used when rendering type names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// portable type names, matching the historical output of `generate_sql`.
+    #[default]
+    Generic,
+    Postgres,
+    /// mysql (innodb): backtick-quoted identifiers and mysql type names such as `TINYINT(1)`.
+    MySql,
+    /// sqlite: type names are its storage class affinities (`INTEGER`, `REAL`, `NUMERIC`, `TEXT`),
+    /// since sqlite ignores lengths such as the `n` of `VARCHAR(n)` anyway.
+    Sqlite,
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "generic" => Ok(Dialect::Generic),
+            "postgres" | "postgresql" => Ok(Dialect::Postgres),
+            "mysql" => Ok(Dialect::MySql),
+            "sqlite" | "sqlite3" => Ok(Dialect::Sqlite),
+            other => Err(format!("unknown sql dialect '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dialect::Generic => write!(f, "generic"),
+            Dialect::Postgres => write!(f, "postgres"),
+            Dialect::MySql => write!(f, "mysql"),
+            Dialect::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+/// additional rendering options for `generate_sql_with_options` and `render_column`.
+/// the default options reproduce the output of `generate_sql`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SqlOptions {
+    /// the columns of a trailing `PRIMARY KEY (...)` constraint, in key order. empty (the
+    /// default) emits no constraint; more than one column declares a composite key.
+    pub primary_key: Vec<String>,
+    /// the allowed values of columns by name, each rendered as an inline
+    /// `CHECK ("col" IN ('a', 'b'))` constraint. empty by default, since the constraints bloat
+    /// the ddl; `enum_check_values` collects them for the low-cardinality text columns.
+    pub check_values: HashMap<String, Vec<String>>,
+    /// the name of an auto-generated integer key column added before the csv columns (an
+    /// `AUTO_INCREMENT` column in mysql, an identity column in postgres). the name is
+    /// snake_cased like the python and django keys (`Item ID` becomes `item_id`), a csv column
+    /// of that name is left out, and `primary_key` is ignored.
+    pub create_primary_key: Option<String>,
+    /// comments by column name, e.g. from `stats_column_comments`. postgres and the generic
+    /// dialect emit `COMMENT ON COLUMN` statements after the `create table`, mysql an inline
+    /// `COMMENT '...'` and sqlite (which has no comments) a `--` line above the column.
+    pub column_comments: HashMap<String, String>,
+    /// the shortest length emitted for a `VARCHAR`, e.g. 255 to leave room for longer values
+    /// than the csv had. lengths are always at least 1.
+    pub varchar_min: usize,
+    /// the longest length emitted for a `VARCHAR`; a column with longer values becomes `TEXT`.
+    /// `None` (the default) sets no limit.
+    pub varchar_max: Option<usize>,
+    /// the layout of the `create table` statement.
+    pub format: SqlFormat,
+    /// leave table and column names unquoted when they need no quotes: lowercase names of
+    /// letters, digits and underscores (`[a-z_][a-z0-9_]*`) that are no reserved word. other
+    /// names are still quoted. off by default, which quotes every name.
+    pub unquoted_identifiers: bool,
+    /// table options appended after the closing parenthesis, in order: postgres and the generic
+    /// dialect render them as storage parameters (`WITH (fillfactor=90)`), mysql as table
+    /// options after the engine (`ROW_FORMAT=DYNAMIC`). sqlite has no such options and ignores
+    /// them. empty by default.
+    pub table_options: Vec<(String, String)>,
+}
+
+/// the layout of a generated `create table` statement, for sql style guides. the default
+/// reproduces the output of `generate_sql`: two-space indentation, the opening parenthesis on
+/// the `CREATE TABLE` line and trailing commas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlFormat {
+    /// the number of spaces before each column definition.
+    pub indent: usize,
+    /// put the opening parenthesis on a line of its own.
+    pub paren_on_new_line: bool,
+    /// start every column definition but the first with its comma (`, "b" INTEGER`) instead of
+    /// ending the previous one with it. the comma goes into the indentation where it fits, so
+    /// the definitions stay aligned.
+    pub leading_commas: bool,
+}
+
+impl Default for SqlFormat {
+    fn default() -> Self {
+        SqlFormat {
+            indent: 2,
+            paren_on_new_line: false,
+            leading_commas: false,
+        }
+    }
+}
+
+impl SqlFormat {
+    /// writes one entry of the column list (a column or a constraint), preceded by the comma
+    /// separating it from the previous entry and by its `--` comment lines.
+    fn write_entry<W: Write>(
+        &self,
+        writer: &mut W,
+        first: bool,
+        comments: &[String],
+        entry: &str,
+    ) -> io::Result<()> {
+        let indent = " ".repeat(self.indent);
+        if !first {
+            writer.write_all(if self.leading_commas { b"\n" } else { b",\n" })?;
+        }
+        for comment in comments {
+            writeln!(writer, "{}-- {}", indent, comment)?;
+        }
+        if self.leading_commas && !first {
+            write!(
+                writer,
+                "{}, {}",
+                " ".repeat(self.indent.saturating_sub(2)),
+                entry
+            )
+        } else {
+            write!(writer, "{}{}", indent, entry)
+        }
+    }
+}
+
+impl SqlOptions {
+    /// returns the column name of `create_primary_key`, sanitized once for every comparison.
+    fn created_key_name(&self) -> Option<String> {
+        self.create_primary_key.as_deref().map(to_snake_case)
+    }
+
+    /// quotes a table or column name unless `unquoted_identifiers` allows it bare.
+    fn identifier(&self, name: &str, dialect: Dialect) -> String {
+        if self.unquoted_identifiers && is_plain_identifier(name) {
+            name.to_string()
+        } else {
+            quote_identifier(name, dialect)
+        }
+    }
+
+    /// applies `varchar_min` and `varchar_max` to the type of a column.
+    fn bounded_type(&self, sql_type: &SqlType) -> SqlType {
+        match sql_type {
+            SqlType::Varchar(len) if self.varchar_max.is_some_and(|max| *len > max) => {
+                SqlType::Text
+            }
+            SqlType::Varchar(len) => SqlType::Varchar(
+                (*len)
+                    .max(self.varchar_min)
+                    .min(self.varchar_max.unwrap_or(usize::MAX)),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// common sql keywords (whitespace-separated), which stay quoted even with
+/// `SqlOptions::unquoted_identifiers`.
+const SQL_RESERVED_WORDS: &str = "\
+    all alter and as asc between by case check column constraint create cross default delete \
+    desc distinct drop else end exists false foreign from full grant group having in index \
+    inner insert into is join key left like limit not null offset on or order outer primary \
+    references right select set table then to true union unique update user using values when \
+    where with";
+
+/// returns true if `name` is a lowercase identifier that needs no quotes in any dialect.
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !SQL_RESERVED_WORDS
+            .split_whitespace()
+            .any(|word| word == name)
+}
+
+/// quotes a table or column name for `dialect`, to handle spaces or special characters. an
+/// embedded quote character is doubled.
+fn quote_identifier(name: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+        Dialect::Generic | Dialect::Postgres | Dialect::Sqlite => {
+            format!("\"{}\"", name.replace('"', "\"\""))
+        }
+    }
+}
+
+/// the names `generate_sql_with_options` gives the columns, which key the per-column options:
+/// the trimmed headers, with repeated ones made unique (`id, id` becomes `id, id_2`).
+fn column_names(headers: &StringRecord) -> Vec<String> {
+    unique_names(headers.iter().map(|header| header.trim().to_string()))
+}
+
+/// collects the `SqlOptions::check_values` of every column with `ColumnStats::enum_values`.
+pub fn enum_check_values(
+    headers: &StringRecord,
+    stats: &[ColumnStats],
+) -> HashMap<String, Vec<String>> {
+    column_names(headers)
+        .into_iter()
+        .zip(stats)
+        .filter_map(|(name, stats)| Some((name, stats.enum_values()?.to_vec())))
+        .collect()
+}
+
+/// describes every column's inference as a `SqlOptions::column_comments` entry, e.g.
+/// `inferred: varchar, nulls: 3, max_len: 8`.
+pub fn stats_column_comments(
+    headers: &StringRecord,
+    stats: &[ColumnStats],
+) -> HashMap<String, String> {
+    column_names(headers)
+        .into_iter()
+        .zip(stats)
+        .map(|(name, stats)| {
+            let comment = format!(
+                "inferred: {}, nulls: {}, max_len: {}",
+                stats.sql_type.kind(),
+                stats.null_count,
+                stats.max_len
+            );
+            (name, comment)
+        })
+        .collect()
+}
+
+/// renders `value` as a single-quoted sql string literal.
+fn sql_string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// renders the sql type name for a column in the given dialect.
+fn sql_type_name(sql_type: &SqlType, dialect: Dialect) -> String {
+    match (sql_type, dialect) {
+        (SqlType::Integer | SqlType::BigInt | SqlType::Boolean, Dialect::Sqlite) => {
+            "INTEGER".to_string()
+        }
+        (SqlType::Float | SqlType::Percentage, Dialect::Sqlite) => "REAL".to_string(),
+        (SqlType::Decimal(..), Dialect::Sqlite) => "NUMERIC".to_string(),
+        // dates and timestamps are stored as iso-8601 text, which sqlite's date functions read.
+        (_, Dialect::Sqlite) => "TEXT".to_string(),
+        (SqlType::Integer, Dialect::MySql) => "INT".to_string(),
+        (SqlType::Integer, _) => "INTEGER".to_string(),
+        (SqlType::BigInt, _) => "BIGINT".to_string(),
+        (SqlType::Float | SqlType::Percentage, Dialect::Postgres) => "DOUBLE PRECISION".to_string(),
+        (SqlType::Float | SqlType::Percentage, Dialect::MySql) => "DOUBLE".to_string(),
+        (SqlType::Float | SqlType::Percentage, _) => "FLOAT".to_string(),
+        (SqlType::Char(len), _) => format!("CHAR({})", len.max(&1)),
+        (SqlType::Varchar(len), _) => format!("VARCHAR({})", len.max(&1)),
+        (SqlType::Text, _) => "TEXT".to_string(),
+        (SqlType::Decimal(precision, scale), _) => {
+            format!("DECIMAL({}, {})", precision.max(&1), scale)
+        }
+        (SqlType::Date, _) => "DATE".to_string(),
+        // mysql's BOOLEAN is only an alias, spelled out so the schema reads like mysql's own.
+        (SqlType::Boolean, Dialect::MySql) => "TINYINT(1)".to_string(),
+        (SqlType::Boolean, _) => "BOOLEAN".to_string(),
+        (SqlType::Datetime, Dialect::Postgres) => "TIMESTAMP".to_string(),
+        (SqlType::Datetime, _) => "DATETIME".to_string(),
+        (SqlType::Inet, Dialect::Postgres) => "INET".to_string(),
+        // the longest textual ipv6 address (with an embedded ipv4 address) has 45 characters.
+        (SqlType::Inet, _) => "VARCHAR(45)".to_string(),
+        (SqlType::Json, Dialect::Postgres) => "JSONB".to_string(),
+        (SqlType::Json, _) => "JSON".to_string(),
+    }
+}
+
+impl SqlType {
+    /// renders the sql type name used for this type in `dialect`, e.g. `DOUBLE PRECISION` for
+    /// a float in postgres. `to_string()` renders the generic dialect.
+    pub fn to_sql_string(&self, dialect: Dialect) -> String {
+        sql_type_name(self, dialect)
+    }
+}
+
+/// the canonical sql rendering of the type in the generic dialect, e.g. `VARCHAR(20)`.
+impl fmt::Display for SqlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&sql_type_name(self, Dialect::Generic))
+    }
+}
+
+/// renders the definition of the `SqlOptions::create_primary_key` column named `name`.
+fn render_created_key(name: &str, dialect: Dialect, options: &SqlOptions) -> String {
+    let quoted_name = options.identifier(name, dialect);
+    match dialect {
+        Dialect::MySql => format!("{} INT NOT NULL AUTO_INCREMENT PRIMARY KEY", quoted_name),
+        Dialect::Postgres => format!(
+            "{} INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY",
+            quoted_name
+        ),
+        // AUTOINCREMENT keeps sqlite from reusing the ids of deleted rows.
+        Dialect::Sqlite => format!("{} INTEGER PRIMARY KEY AUTOINCREMENT", quoted_name),
+        Dialect::Generic => format!("{} INTEGER NOT NULL PRIMARY KEY", quoted_name),
+    }
+}
+
+/// renders a single column definition fragment, `"name" TYPE [NOT NULL]`, as used
+/// inside a `create table` statement. the fragment carries no indentation or trailing comma.
+pub fn render_column(
+    name: &str,
+    sql_type: &SqlType,
+    nullable: bool,
+    dialect: Dialect,
+    options: &SqlOptions,
+) -> String {
+    let quoted_name = options.identifier(name, dialect);
+    let mut column = format!(
+        "{} {}",
+        quoted_name,
+        options.bounded_type(sql_type).to_sql_string(dialect)
+    );
+    if !nullable {
+        column.push_str(" NOT NULL");
+    }
+    if let Some(values) = options.check_values.get(name.trim()) {
+        let literals: Vec<String> = values
+            .iter()
+            .map(|value| sql_string_literal(value))
+            .collect();
+        column.push_str(&format!(
+            " CHECK ({} IN ({}))",
+            quoted_name,
+            literals.join(", ")
+        ));
+    }
+    if dialect == Dialect::MySql
+        && let Some(comment) = options.column_comments.get(name.trim())
+    {
+        column.push_str(&format!(" COMMENT {}", sql_string_literal(comment)));
+    }
+    column
+}
 
 /// generates a `create table` sql statement from a table name, headers, and inferred types.
 pub fn generate_sql(table_name: &str, headers: &StringRecord, types: &[SqlType]) -> String {
-    // quote the table name to handle names with spaces or special characters.
-    let mut sql = format!("CREATE TABLE \"{}\" (\n", table_name);
+    write_to_string(|writer| generate_sql_to(writer, table_name, headers, types))
+}
+
+/// writes the statement of `generate_sql` to `writer`.
+pub fn generate_sql_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+) -> io::Result<()> {
+    generate_sql_with_options_to(
+        writer,
+        table_name,
+        headers,
+        types,
+        Dialect::Generic,
+        &SqlOptions::default(),
+    )
+}
 
-    let columns: Vec<String> = headers
+/// generates a `create table` sql statement for a specific dialect.
+pub fn generate_sql_with_options(
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    dialect: Dialect,
+    options: &SqlOptions,
+) -> String {
+    write_to_string(|writer| {
+        generate_sql_with_options_to(writer, table_name, headers, types, dialect, options)
+    })
+}
+
+/// writes the statement of `generate_sql_with_options` to `writer`.
+pub fn generate_sql_with_options_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    dialect: Dialect,
+    options: &SqlOptions,
+) -> io::Result<()> {
+    let format = &options.format;
+    let open_paren = if format.paren_on_new_line {
+        "\n("
+    } else {
+        " ("
+    };
+    writeln!(
+        writer,
+        "CREATE TABLE {}{}",
+        options.identifier(table_name, dialect),
+        open_paren
+    )?;
+
+    let mut first = true;
+    let created_key_name = options.created_key_name();
+    // a csv column is replaced by the created key when their python field names match.
+    let is_created_key = |name: &str| {
+        created_key_name
+            .as_deref()
+            .is_some_and(|key_name| to_snake_case(name) == key_name)
+    };
+    if let Some(key_name) = &created_key_name {
+        let key = render_created_key(key_name, dialect, options);
+        format.write_entry(writer, first, &[], &key)?;
+        first = false;
+    }
+
+    // repeated headers (`id, name, id`) would declare the same column twice.
+    let column_names = column_names(headers);
+    for (name, sql_type) in column_names.iter().zip(types.iter()) {
+        if is_created_key(name) {
+            continue;
+        }
+        let mut comments = Vec::new();
+        if *sql_type == SqlType::Percentage {
+            comments.push("percentage: a value of 45 means 45%".to_string());
+        }
+        if dialect == Dialect::Sqlite
+            && let Some(comment) = options.column_comments.get(name.as_str())
+        {
+            // a line comment ends at the newline, which must not sneak into the comment.
+            comments.push(comment.replace('\n', " "));
+        }
+        // inferred columns may contain empty values, so they are always rendered nullable.
+        let column = render_column(name, sql_type, true, dialect, options);
+        format.write_entry(writer, first, &comments, &column)?;
+        first = false;
+    }
+
+    if !options.primary_key.is_empty() && options.create_primary_key.is_none() {
+        let key_columns: Vec<String> = options
+            .primary_key
+            .iter()
+            .map(|column| options.identifier(column.trim(), dialect))
+            .collect();
+        let constraint = format!("PRIMARY KEY ({})", key_columns.join(", "));
+        format.write_entry(writer, first, &[], &constraint)?;
+    }
+
+    let table_options: Vec<String> = options
+        .table_options
         .iter()
-        .zip(types.iter())
-        .map(|(header, sql_type)| {
-            let type_str = match sql_type {
-                SqlType::Integer => "INTEGER".to_string(),
-                SqlType::BigInt => "BIGINT".to_string(),
-                SqlType::Float => "FLOAT".to_string(),
-                SqlType::Char(len) => format!("CHAR({})", len.max(&1)),
-                SqlType::Varchar(len) => format!("VARCHAR({})", len.max(&1)),
-                SqlType::Date => "DATE".to_string(),
-                SqlType::Boolean => "BOOLEAN".to_string(),
-                SqlType::Datetime => "DATETIME".to_string(),
-            };
-            // quote column names to handle spaces or special characters.
-            format!("  \"{}\" {}", header.trim(), type_str)
-        })
+        .map(|(key, value)| format!("{}={}", key, value))
         .collect();
+    match dialect {
+        Dialect::MySql => {
+            writer.write_all(b"\n) ENGINE=InnoDB")?;
+            for table_option in &table_options {
+                write!(writer, " {}", table_option)?;
+            }
+            writer.write_all(b";")?;
+        }
+        Dialect::Generic | Dialect::Postgres if !table_options.is_empty() => {
+            write!(writer, "\n) WITH ({});", table_options.join(", "))?;
+        }
+        Dialect::Generic | Dialect::Postgres | Dialect::Sqlite => writer.write_all(b"\n);")?,
+    }
+
+    if matches!(dialect, Dialect::Generic | Dialect::Postgres) {
+        for name in column_names.iter().filter(|name| !is_created_key(name)) {
+            if let Some(comment) = options.column_comments.get(name.as_str()) {
+                write!(
+                    writer,
+                    "\nCOMMENT ON COLUMN {}.{} IS {};",
+                    options.identifier(table_name, dialect),
+                    options.identifier(name, dialect),
+                    sql_string_literal(comment)
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// generates the `alter table` statements that migrate a table from the `old` schema to the
+/// `new` one, e.g. when a daily csv gained a column or its values grew. columns are matched by
+/// name: new columns are added, missing ones dropped, and columns whose type name changed in
+/// `dialect` are altered. a change that is not a `SqlType::widens_to` widening may fail on or
+/// truncate existing values, so it is preceded by a `-- narrowing` comment (and converted with
+/// `USING` in postgres). sqlite cannot alter a column type, so its changes are only comments.
+/// identical schemas give an empty string.
+pub fn generate_sql_diff(
+    table_name: &str,
+    old: &(StringRecord, Vec<SqlType>),
+    new: &(StringRecord, Vec<SqlType>),
+    dialect: Dialect,
+) -> String {
+    write_to_string(|writer| generate_sql_diff_to(writer, table_name, old, new, dialect))
+}
+
+/// writes the statements of `generate_sql_diff` to `writer`.
+pub fn generate_sql_diff_to<W: Write>(
+    writer: &mut W,
+    table_name: &str,
+    old: &(StringRecord, Vec<SqlType>),
+    new: &(StringRecord, Vec<SqlType>),
+    dialect: Dialect,
+) -> io::Result<()> {
+    let table = quote_identifier(table_name, dialect);
+    let old_names = unique_names(old.0.iter().map(|header| header.trim().to_string()));
+    let new_names = unique_names(new.0.iter().map(|header| header.trim().to_string()));
+
+    let mut statements = Vec::new();
+    for (name, new_type) in new_names.iter().zip(&new.1) {
+        let Some(old_index) = old_names.iter().position(|old_name| old_name == name) else {
+            // inferred columns may contain empty values, so they are always added nullable.
+            let column = render_column(name, new_type, true, dialect, &SqlOptions::default());
+            statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table, column));
+            continue;
+        };
+        let old_type = &old.1[old_index];
+        let old_type_name = old_type.to_sql_string(dialect);
+        let new_type_name = new_type.to_sql_string(dialect);
+        if old_type_name == new_type_name {
+            continue;
+        }
+
+        let column = quote_identifier(name, dialect);
+        let widening = old_type.widens_to(new_type);
+        if !widening {
+            statements.push(format!(
+                "-- narrowing {} from {} to {}: existing values may not convert",
+                column, old_type_name, new_type_name
+            ));
+        }
+        statements.push(match dialect {
+            Dialect::MySql => format!(
+                "ALTER TABLE {} MODIFY COLUMN {} {};",
+                table, column, new_type_name
+            ),
+            Dialect::Postgres if !widening => format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+                table, column, new_type_name, column, new_type_name
+            ),
+            Dialect::Generic | Dialect::Postgres => format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+                table, column, new_type_name
+            ),
+            Dialect::Sqlite => format!(
+                "-- sqlite cannot change the type of {} to {}; the table has to be rebuilt",
+                column, new_type_name
+            ),
+        });
+    }
+    for name in old_names.iter().filter(|name| !new_names.contains(name)) {
+        statements.push(format!(
+            "ALTER TABLE {} DROP COLUMN {};",
+            table,
+            quote_identifier(name, dialect)
+        ));
+    }
+
+    writer.write_all(statements.join("\n").as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_sql_diff_added_column_and_widened_type() {
+        let old = (
+            StringRecord::from(vec!["id", "name"]),
+            vec![SqlType::Integer, SqlType::Varchar(10)],
+        );
+        let new = (
+            StringRecord::from(vec!["id", "name", "email"]),
+            vec![SqlType::BigInt, SqlType::Varchar(10), SqlType::Varchar(30)],
+        );
+        assert_eq!(
+            generate_sql_diff("users", &old, &new, Dialect::Postgres),
+            "ALTER TABLE \"users\" ALTER COLUMN \"id\" TYPE BIGINT;\n\
+             ALTER TABLE \"users\" ADD COLUMN \"email\" VARCHAR(30);"
+        );
+        assert_eq!(
+            generate_sql_diff("users", &new, &new, Dialect::Postgres),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_diff_narrowing_and_dropped_column() {
+        let old = (
+            StringRecord::from(vec!["id", "price", "note"]),
+            vec![SqlType::BigInt, SqlType::Float, SqlType::Text],
+        );
+        let new = (
+            StringRecord::from(vec!["id", "price"]),
+            vec![SqlType::BigInt, SqlType::Decimal(6, 2)],
+        );
+        assert_eq!(
+            generate_sql_diff("items", &old, &new, Dialect::Postgres),
+            "-- narrowing \"price\" from DOUBLE PRECISION to DECIMAL(6, 2): existing values may \
+             not convert\nALTER TABLE \"items\" ALTER COLUMN \"price\" TYPE DECIMAL(6, 2) USING \
+             \"price\"::DECIMAL(6, 2);\nALTER TABLE \"items\" DROP COLUMN \"note\";"
+        );
+        assert_eq!(
+            generate_sql_diff("items", &old, &new, Dialect::MySql),
+            "-- narrowing `price` from DOUBLE to DECIMAL(6, 2): existing values may not convert\n\
+             ALTER TABLE `items` MODIFY COLUMN `price` DECIMAL(6, 2);\n\
+             ALTER TABLE `items` DROP COLUMN `note`;"
+        );
+    }
+
+    #[test]
+    fn test_render_column_generic() {
+        let options = SqlOptions::default();
+        assert_eq!(
+            render_column("age", &SqlType::Integer, true, Dialect::Generic, &options),
+            "\"age\" INTEGER"
+        );
+        assert_eq!(
+            render_column("code", &SqlType::Char(0), false, Dialect::Generic, &options),
+            "\"code\" CHAR(1) NOT NULL"
+        );
+        assert_eq!(
+            render_column(
+                "first name",
+                &SqlType::Varchar(12),
+                true,
+                Dialect::Generic,
+                &options
+            ),
+            "\"first name\" VARCHAR(12)"
+        );
+        assert_eq!(
+            render_column(
+                "price",
+                &SqlType::Decimal(6, 2),
+                true,
+                Dialect::Generic,
+                &options
+            ),
+            "\"price\" DECIMAL(6, 2)"
+        );
+    }
+
+    #[test]
+    fn test_render_column_postgres() {
+        let options = SqlOptions::default();
+        assert_eq!(
+            render_column(
+                "created_at",
+                &SqlType::Datetime,
+                false,
+                Dialect::Postgres,
+                &options
+            ),
+            "\"created_at\" TIMESTAMP NOT NULL"
+        );
+        assert_eq!(
+            render_column("price", &SqlType::Float, true, Dialect::Postgres, &options),
+            "\"price\" DOUBLE PRECISION"
+        );
+        assert_eq!(
+            render_column(
+                "is_active",
+                &SqlType::Boolean,
+                true,
+                Dialect::Postgres,
+                &options
+            ),
+            "\"is_active\" BOOLEAN"
+        );
+    }
+
+    #[test]
+    fn test_render_column_check_constraint() {
+        let options = SqlOptions {
+            check_values: HashMap::from([(
+                "status".to_string(),
+                vec!["open".to_string(), "won't fix".to_string()],
+            )]),
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            render_column(
+                "status",
+                &SqlType::Varchar(9),
+                true,
+                Dialect::Generic,
+                &options
+            ),
+            "\"status\" VARCHAR(9) CHECK (\"status\" IN ('open', 'won''t fix'))"
+        );
+        assert_eq!(
+            render_column("id", &SqlType::Integer, true, Dialect::Generic, &options),
+            "\"id\" INTEGER"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_preserves_header_case() {
+        let headers = StringRecord::from(vec!["OrderID", " CustomerName "]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20)];
+        assert_eq!(
+            generate_sql("Orders", &headers, &types),
+            "CREATE TABLE \"Orders\" (\n  \"OrderID\" INTEGER,\n  \"CustomerName\" VARCHAR(20)\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_with_format() {
+        let headers = StringRecord::from(vec!["id", "name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(10)];
+        let mut options = SqlOptions {
+            primary_key: vec!["id".to_string()],
+            format: SqlFormat {
+                indent: 4,
+                ..SqlFormat::default()
+            },
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("users", &headers, &types, Dialect::Generic, &options),
+            "CREATE TABLE \"users\" (\n    \"id\" INTEGER,\n    \"name\" VARCHAR(10),\n    \
+             PRIMARY KEY (\"id\")\n);"
+        );
+
+        options.format.paren_on_new_line = true;
+        options.format.leading_commas = true;
+        assert_eq!(
+            generate_sql_with_options("users", &headers, &types, Dialect::Generic, &options),
+            "CREATE TABLE \"users\"\n(\n    \"id\" INTEGER\n  , \"name\" VARCHAR(10)\n  , \
+             PRIMARY KEY (\"id\")\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_mysql_backticks() {
+        let headers = StringRecord::from(vec!["user id", "active", "odd`name"]);
+        let types = vec![SqlType::Integer, SqlType::Boolean, SqlType::Float];
+        let options = SqlOptions {
+            primary_key: vec!["user id".to_string()],
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("users", &headers, &types, Dialect::MySql, &options),
+            "CREATE TABLE `users` (\n  `user id` INT,\n  `active` TINYINT(1),\n  \
+             `odd``name` DOUBLE,\n  PRIMARY KEY (`user id`)\n) ENGINE=InnoDB;"
+        );
+        assert_eq!("MySQL".parse::<Dialect>(), Ok(Dialect::MySql));
+    }
+
+    #[test]
+    fn test_generate_sql_doubles_embedded_quotes() {
+        let headers = StringRecord::from(vec!["size \"in\""]);
+        let types = vec![SqlType::Float];
+        let options = SqlOptions {
+            column_comments: HashMap::from([("size \"in\"".to_string(), "inches".to_string())]),
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("items", &headers, &types, Dialect::Postgres, &options),
+            "CREATE TABLE \"items\" (\n  \"size \"\"in\"\"\" DOUBLE PRECISION\n);\n\
+             COMMENT ON COLUMN \"items\".\"size \"\"in\"\"\" IS 'inches';"
+        );
+
+        let old = (StringRecord::from(vec!["id"]), vec![SqlType::Integer]);
+        let new = (headers, types);
+        assert_eq!(
+            generate_sql_diff("items", &old, &new, Dialect::Sqlite),
+            "ALTER TABLE \"items\" ADD COLUMN \"size \"\"in\"\"\" REAL;\n\
+             ALTER TABLE \"items\" DROP COLUMN \"id\";"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_created_primary_key() {
+        let headers = StringRecord::from(vec!["id", "name"]);
+        let types = vec![SqlType::Varchar(4), SqlType::Varchar(10)];
+        let options = SqlOptions {
+            create_primary_key: Some("id".to_string()),
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("items", &headers, &types, Dialect::MySql, &options),
+            "CREATE TABLE `items` (\n  `id` INT NOT NULL AUTO_INCREMENT PRIMARY KEY,\n  \
+             `name` VARCHAR(10)\n) ENGINE=InnoDB;"
+        );
+        assert_eq!(
+            generate_sql_with_options("items", &headers, &types, Dialect::Postgres, &options),
+            "CREATE TABLE \"items\" (\n  \"id\" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY \
+             KEY,\n  \"name\" VARCHAR(10)\n);"
+        );
+
+        // the key name is sanitized like the python key, so it replaces the `item_id` column.
+        let headers = StringRecord::from(vec!["item_id", "name"]);
+        let options = SqlOptions {
+            create_primary_key: Some("Item ID".to_string()),
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("items", &headers, &types, Dialect::Generic, &options),
+            "CREATE TABLE \"items\" (\n  \"item_id\" INTEGER NOT NULL PRIMARY KEY,\n  \
+             \"name\" VARCHAR(10)\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_sqlite_affinities() {
+        let headers = StringRecord::from(vec!["id", "born", "active", "score", "name", "price"]);
+        let types = vec![
+            SqlType::Varchar(4),
+            SqlType::Date,
+            SqlType::Boolean,
+            SqlType::Float,
+            SqlType::Varchar(30),
+            SqlType::Decimal(6, 2),
+        ];
+        let options = SqlOptions {
+            create_primary_key: Some("id".to_string()),
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("people", &headers, &types, Dialect::Sqlite, &options),
+            "CREATE TABLE \"people\" (\n  \"id\" INTEGER PRIMARY KEY AUTOINCREMENT,\n  \
+             \"born\" TEXT,\n  \"active\" INTEGER,\n  \"score\" REAL,\n  \"name\" TEXT,\n  \
+             \"price\" NUMERIC\n);"
+        );
+        assert_eq!(SqlType::BigInt.to_sql_string(Dialect::Sqlite), "INTEGER");
+        assert_eq!(SqlType::Datetime.to_sql_string(Dialect::Sqlite), "TEXT");
+    }
+
+    #[test]
+    fn test_generate_sql_with_postgres_dialect() {
+        let headers = StringRecord::from(vec!["id", "updated"]);
+        let types = vec![SqlType::BigInt, SqlType::Datetime];
+        let sql = generate_sql_with_options(
+            "events",
+            &headers,
+            &types,
+            Dialect::Postgres,
+            &SqlOptions::default(),
+        );
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"events\" (\n  \"id\" BIGINT,\n  \"updated\" TIMESTAMP\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_to_matches_string_api() {
+        let headers = StringRecord::from(vec!["id", "name"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(20)];
+        let mut buffer = Vec::new();
+        generate_sql_to(&mut buffer, "users", &headers, &types).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            generate_sql("users", &headers, &types)
+        );
+    }
+
+    #[test]
+    fn test_sql_type_display() {
+        let cases = [
+            (SqlType::Integer, "INTEGER"),
+            (SqlType::BigInt, "BIGINT"),
+            (SqlType::Float, "FLOAT"),
+            (SqlType::Char(3), "CHAR(3)"),
+            (SqlType::Char(0), "CHAR(1)"),
+            (SqlType::Varchar(20), "VARCHAR(20)"),
+            (SqlType::Varchar(0), "VARCHAR(1)"),
+            (SqlType::Text, "TEXT"),
+            (SqlType::Decimal(10, 2), "DECIMAL(10, 2)"),
+            (SqlType::Percentage, "FLOAT"),
+            (SqlType::Date, "DATE"),
+            (SqlType::Boolean, "BOOLEAN"),
+            (SqlType::Datetime, "DATETIME"),
+            (SqlType::Inet, "VARCHAR(45)"),
+            (SqlType::Json, "JSON"),
+        ];
+        for (sql_type, expected) in cases {
+            assert_eq!(sql_type.to_string(), expected);
+            assert_eq!(sql_type.to_sql_string(Dialect::Generic), expected);
+        }
+        assert_eq!(
+            SqlType::Float.to_sql_string(Dialect::Postgres),
+            "DOUBLE PRECISION"
+        );
+        assert_eq!(
+            SqlType::Datetime.to_sql_string(Dialect::Postgres),
+            "TIMESTAMP"
+        );
+    }
+
+    #[test]
+    fn test_inet_is_native_on_postgres_only() {
+        assert_eq!(sql_type_name(&SqlType::Inet, Dialect::Postgres), "INET");
+        assert_eq!(
+            sql_type_name(&SqlType::Inet, Dialect::Generic),
+            "VARCHAR(45)"
+        );
+    }
+
+    #[test]
+    fn test_json_type_names() {
+        assert_eq!(sql_type_name(&SqlType::Json, Dialect::Postgres), "JSONB");
+        assert_eq!(sql_type_name(&SqlType::Json, Dialect::Generic), "JSON");
+    }
+
+    #[test]
+    fn test_generate_sql_disambiguates_duplicate_headers() {
+        let headers = StringRecord::from(vec!["id", "name", " id"]);
+        let types = vec![SqlType::Integer, SqlType::Varchar(10), SqlType::Integer];
+        assert_eq!(
+            generate_sql("people", &headers, &types),
+            "CREATE TABLE \"people\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(10),\n  \
+             \"id_2\" INTEGER\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_composite_primary_key() {
+        let headers = StringRecord::from(vec!["student_id", "course_id", "grade"]);
+        let types = vec![SqlType::Integer, SqlType::Integer, SqlType::Char(1)];
+        let options = SqlOptions {
+            primary_key: vec!["student_id".to_string(), "course_id".to_string()],
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("enrollments", &headers, &types, Dialect::Generic, &options),
+            "CREATE TABLE \"enrollments\" (\n  \"student_id\" INTEGER,\n  \"course_id\" INTEGER,\n  \
+             \"grade\" CHAR(1),\n  PRIMARY KEY (\"student_id\", \"course_id\")\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_comments_percentage_columns() {
+        let headers = StringRecord::from(vec!["id", "rate"]);
+        let types = vec![SqlType::Integer, SqlType::Percentage];
+        assert_eq!(
+            generate_sql("stats", &headers, &types),
+            "CREATE TABLE \"stats\" (\n  \"id\" INTEGER,\n  \
+             -- percentage: a value of 45 means 45%\n  \"rate\" FLOAT\n);"
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_column_comments() {
+        let csv_data = "id,name\n1,Alice\n2,\n3,\n4,Bob\n5,\n";
+        let (headers, stats) =
+            crate::infer_schema_with_stats(csv_data.as_bytes(), &Default::default()).unwrap();
+        let types: Vec<SqlType> = stats.iter().map(|stats| stats.sql_type.clone()).collect();
+        let options = SqlOptions {
+            column_comments: stats_column_comments(&headers, &stats),
+            ..SqlOptions::default()
+        };
+
+        assert_eq!(
+            generate_sql_with_options("t", &headers, &types, Dialect::Postgres, &options),
+            "CREATE TABLE \"t\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(5)\n);\n\
+             COMMENT ON COLUMN \"t\".\"id\" IS 'inferred: integer, nulls: 0, max_len: 1';\n\
+             COMMENT ON COLUMN \"t\".\"name\" IS 'inferred: varchar, nulls: 3, max_len: 5';"
+        );
+        assert!(
+            generate_sql_with_options("t", &headers, &types, Dialect::MySql, &options).contains(
+                "  `name` VARCHAR(5) COMMENT 'inferred: varchar, nulls: 3, max_len: 5'\n"
+            )
+        );
+        assert!(
+            generate_sql_with_options("t", &headers, &types, Dialect::Sqlite, &options)
+                .contains("  -- inferred: varchar, nulls: 3, max_len: 5\n  \"name\" TEXT")
+        );
+
+        // the created key replaces the csv id column, whose stats do not describe it.
+        let options = SqlOptions {
+            create_primary_key: Some("id".to_string()),
+            ..options
+        };
+        let sql = generate_sql_with_options("t", &headers, &types, Dialect::Postgres, &options);
+        assert!(!sql.contains("COMMENT ON COLUMN \"t\".\"id\""));
+        assert!(sql.contains("COMMENT ON COLUMN \"t\".\"name\""));
+    }
+
+    #[test]
+    fn test_per_column_options_of_repeated_headers() {
+        let csv_data = "id,status,Status,id\n1,open,a,x\n2,closed,a,y\n3,open,b,x\n";
+        let (headers, stats) =
+            crate::infer_schema_with_stats(csv_data.as_bytes(), &Default::default()).unwrap();
+        let check_values = enum_check_values(&headers, &stats);
+        assert_eq!(check_values.get("id"), None);
+        assert_eq!(
+            check_values.get("id_2"),
+            Some(&vec!["x".to_string(), "y".to_string()])
+        );
+        let comments = stats_column_comments(&headers, &stats);
+        assert_eq!(comments["id"], "inferred: integer, nulls: 0, max_len: 1");
+        assert_eq!(comments["id_2"], "inferred: char, nulls: 0, max_len: 1");
+    }
+
+    #[test]
+    fn test_varchar_floor() {
+        let options = SqlOptions {
+            varchar_min: 255,
+            ..SqlOptions::default()
+        };
+        let render =
+            |sql_type: &SqlType| render_column("c", sql_type, true, Dialect::Generic, &options);
+        assert_eq!(render(&SqlType::Varchar(12)), "\"c\" VARCHAR(255)");
+        assert_eq!(render(&SqlType::Varchar(300)), "\"c\" VARCHAR(300)");
+        // char columns keep their exact length.
+        assert_eq!(render(&SqlType::Char(2)), "\"c\" CHAR(2)");
+    }
+
+    #[test]
+    fn test_varchar_ceiling_promotes_to_text() {
+        let options = SqlOptions {
+            varchar_min: 100,
+            varchar_max: Some(1000),
+            ..SqlOptions::default()
+        };
+        let render =
+            |sql_type: &SqlType| render_column("c", sql_type, true, Dialect::MySql, &options);
+        assert_eq!(render(&SqlType::Varchar(1000)), "`c` VARCHAR(1000)");
+        assert_eq!(render(&SqlType::Varchar(1001)), "`c` TEXT");
+        assert_eq!(render(&SqlType::Varchar(0)), "`c` VARCHAR(100)");
+    }
+
+    #[test]
+    fn test_generate_sql_table_options() {
+        let headers = StringRecord::from(vec!["id"]);
+        let types = vec![SqlType::Integer];
+        let options = SqlOptions {
+            table_options: vec![("fillfactor".to_string(), "90".to_string())],
+            ..SqlOptions::default()
+        };
+        let render =
+            |dialect| generate_sql_with_options("events", &headers, &types, dialect, &options);
+        assert_eq!(
+            render(Dialect::Postgres),
+            "CREATE TABLE \"events\" (\n  \"id\" INTEGER\n) WITH (fillfactor=90);"
+        );
+        assert!(render(Dialect::Sqlite).ends_with("\n);"));
+
+        let options = SqlOptions {
+            table_options: vec![("ROW_FORMAT".to_string(), "DYNAMIC".to_string())],
+            ..SqlOptions::default()
+        };
+        assert!(
+            generate_sql_with_options("events", &headers, &types, Dialect::MySql, &options)
+                .ends_with("\n) ENGINE=InnoDB ROW_FORMAT=DYNAMIC;")
+        );
+    }
 
-    sql.push_str(&columns.join(",\n"));
-    sql.push_str("\n);");
+    #[test]
+    fn test_generate_sql_unquoted_identifiers() {
+        let headers = StringRecord::from(vec!["city", "user name", "order", "Zip"]);
+        let types = vec![SqlType::Varchar(20); 4];
+        let options = SqlOptions {
+            unquoted_identifiers: true,
+            ..SqlOptions::default()
+        };
+        assert_eq!(
+            generate_sql_with_options("places", &headers, &types, Dialect::Postgres, &options),
+            "CREATE TABLE places (\n  city VARCHAR(20),\n  \"user name\" VARCHAR(20),\n  \
+             \"order\" VARCHAR(20),\n  \"Zip\" VARCHAR(20)\n);"
+        );
+        // quoting every name stays the default.
+        assert!(generate_sql("places", &headers, &types).contains("  \"city\" VARCHAR(20),\n"));
+    }
 
-    sql
+    #[test]
+    fn test_dialect_from_str() {
+        assert_eq!("postgresql".parse::<Dialect>(), Ok(Dialect::Postgres));
+        assert_eq!("Generic".parse::<Dialect>(), Ok(Dialect::Generic));
+        assert!("oracle".parse::<Dialect>().is_err());
+    }
 }
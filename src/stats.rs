@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+
+use super::SqlType;
+use crate::InferenceOptions;
+use crate::type_inference::ColumnInferenceState;
+
+/// the number of distinct values tracked per column before `distinct_count` gives up.
+/// every tracked value is kept in memory, so profiling costs up to
+/// `DISTINCT_LIMIT * max_len` bytes per column on top of the streaming inference.
+pub const DISTINCT_LIMIT: usize = 10_000;
+
+/// the number of distinct non-empty values kept as `sample_values`.
+pub const SAMPLE_SIZE: usize = 5;
+
+/// the number of non-empty values below which a column's type counts as a guess.
+const FEW_VALUES: usize = 5;
+
+/// what the values of a column represent, beyond their sql type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticHint {
+    /// every non-empty value looks like an email address (`local@domain.tld`).
+    Email,
+    /// the values look like hex digests (md5, sha, ...) of one length, see
+    /// `InferenceOptions::detect_hex_digests`.
+    HexDigest,
+}
+
+/// returns true if `value` has the basic shape of an email address: a non-empty local part,
+/// a single `@`, and a domain of non-empty dot-separated labels ending in an alphabetic
+/// top-level domain of at least two letters. no whitespace is allowed anywhere.
+fn is_email_value(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    if local.is_empty() || domain.contains('@') || value.contains(char::is_whitespace) {
+        return false;
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    let tld = labels[labels.len() - 1];
+    labels.len() >= 2
+        && labels.iter().all(|label| !label.is_empty())
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// a profile of one csv column, as returned by `infer_schema_with_stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// the inferred type, identical to what `infer_schema` reports.
+    pub sql_type: SqlType,
+    /// the number of records scanned, i.e. the values seen in this column including empty ones.
+    /// 0 means the input had a header but no data rows.
+    pub row_count: usize,
+    /// the number of empty values (including configured null tokens).
+    pub null_count: usize,
+    /// the number of distinct non-empty values, or `None` if there were more than
+    /// `DISTINCT_LIMIT` of them.
+    pub distinct_count: Option<usize>,
+    /// the byte length of the shortest non-empty value (0 if there is none).
+    pub min_len: usize,
+    /// the byte length of the longest value, counting embedded line breaks (see `infer_sql_type`).
+    pub max_len: usize,
+    /// for a `Float` column, the most digits any value had before the decimal point, e.g. 4
+    /// for `1,234.5`; `None` for other types. together with `max_fractional_digits` this tells
+    /// whether a `DECIMAL(p, s)` would hold the values exactly.
+    pub max_integer_digits: Option<usize>,
+    /// for a `Float` column, the most digits any value had after the decimal point (the
+    /// observed scale); `None` for other types.
+    pub max_fractional_digits: Option<usize>,
+    /// the first `SAMPLE_SIZE` distinct non-empty values, in input order.
+    pub sample_values: Vec<String>,
+    /// what the values represent, if detected. only set by the detections enabled in
+    /// `InferenceOptions` (e.g. `detect_emails`); it never changes `sql_type`.
+    pub semantic: Option<SemanticHint>,
+    /// how much the values support `sql_type`, from 0 (a default for a column without values)
+    /// to 1. every entry of `reasons` lowers it.
+    pub confidence: f32,
+    /// why `sql_type` may be a risky guess, e.g. `boolean inferred from only 1/0` or `90% null`.
+    /// empty for a confident result.
+    pub reasons: Vec<String>,
+}
+
+impl ColumnStats {
+    /// returns every distinct value of a low-cardinality text column (a status, a category, a
+    /// `Y`/`N` flag stored as text), in input order: a `Char`/`Varchar` column with at most
+    /// `SAMPLE_SIZE` distinct values, at least one of which repeats. `None` for other columns.
+    pub fn enum_values(&self) -> Option<&[String]> {
+        let distinct = self.distinct_count?;
+        let is_text = matches!(self.sql_type, SqlType::Char(_) | SqlType::Varchar(_));
+        // below `SAMPLE_SIZE` distinct values, `sample_values` holds all of them.
+        (is_text
+            && distinct > 0
+            && distinct <= SAMPLE_SIZE
+            && distinct < self.row_count - self.null_count)
+            .then_some(self.sample_values.as_slice())
+    }
+}
+
+/// the running state behind a `ColumnStats`: the type inference state plus the profiling
+/// counters, folded one value at a time.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnStatsCollector<'a> {
+    options: &'a InferenceOptions,
+    inference: ColumnInferenceState<'a>,
+    min_len: Option<usize>,
+    // `None` once more than `DISTINCT_LIMIT` distinct values were seen.
+    distinct: Option<HashSet<String>>,
+    sample_values: Vec<String>,
+    // true while every non-empty value looked like an email (and detection is enabled).
+    all_emails: bool,
+    // true while every non-empty value was `0` or `1`, which may be a flag or a number.
+    all_binary_digits: bool,
+}
+
+impl<'a> ColumnStatsCollector<'a> {
+    pub(crate) fn new(options: &'a InferenceOptions) -> Self {
+        ColumnStatsCollector {
+            options,
+            inference: ColumnInferenceState::new(options),
+            min_len: None,
+            distinct: Some(HashSet::new()),
+            sample_values: Vec::new(),
+            all_emails: options.detect_emails,
+            all_binary_digits: true,
+        }
+    }
+
+    pub(crate) fn observe(&mut self, value: &str) {
+        self.inference.observe(value);
+        if value.is_empty() {
+            return;
+        }
+
+        if self.all_emails && !is_email_value(value) {
+            self.all_emails = false;
+        }
+        if self.all_binary_digits && value != "0" && value != "1" {
+            self.all_binary_digits = false;
+        }
+
+        self.min_len = Some(self.min_len.map_or(value.len(), |len| len.min(value.len())));
+
+        if self.sample_values.len() < SAMPLE_SIZE && !self.sample_values.iter().any(|v| v == value)
+        {
+            self.sample_values.push(value.to_string());
+        }
+
+        if let Some(distinct) = &mut self.distinct
+            && !distinct.contains(value)
+        {
+            if distinct.len() == DISTINCT_LIMIT {
+                // free the tracked values, the exact count is no longer needed.
+                self.distinct = None;
+            } else {
+                distinct.insert(value.to_string());
+            }
+        }
+    }
+
+    // the profiling counters keep covering every value, only the type is verified.
+    pub(crate) fn start_verifying(&mut self) {
+        self.inference.start_verifying();
+    }
+
+    /// rates the inferred type, see `ColumnStats::confidence`.
+    fn confidence(&self, sql_type: &SqlType) -> (f32, Vec<String>) {
+        let row_count = self.inference.value_count();
+        let null_count = self.inference.empty_count();
+        let value_count = row_count - null_count;
+        if value_count == 0 {
+            return (0.0, vec!["no non-empty values".to_string()]);
+        }
+
+        let mut confidence = 1.0;
+        let mut reasons = Vec::new();
+        // a mostly empty column rests on the few values it has.
+        if null_count * 2 >= row_count {
+            confidence *= value_count as f32 / row_count as f32;
+            reasons.push(format!("{}% null", null_count * 100 / row_count));
+        }
+        if value_count < FEW_VALUES {
+            confidence *= 0.5;
+            let noun = if value_count == 1 { "value" } else { "values" };
+            reasons.push(format!("only {} non-empty {}", value_count, noun));
+        }
+        if *sql_type == SqlType::Boolean && self.all_binary_digits {
+            confidence *= 0.5;
+            reasons.push("boolean inferred from only 1/0".to_string());
+        }
+        (confidence, reasons)
+    }
+
+    // the type overrides of `options` apply before rating, so a column forced to another type is
+    // not reported with the reasons of the inferred one.
+    pub(crate) fn finish(self, header: &str) -> ColumnStats {
+        let sql_type =
+            self.options
+                .resolve_type(header, self.inference.finalize(), self.inference.max_len());
+        let (confidence, reasons) = self.confidence(&sql_type);
+        let float_digits = self
+            .inference
+            .float_digits()
+            .filter(|_| sql_type == SqlType::Float);
+        ColumnStats {
+            sql_type,
+            row_count: self.inference.value_count(),
+            null_count: self.inference.empty_count(),
+            distinct_count: self.distinct.map(|distinct| distinct.len()),
+            min_len: self.min_len.unwrap_or(0),
+            max_len: self.inference.max_len(),
+            max_integer_digits: float_digits.map(|(integer_digits, _)| integer_digits),
+            max_fractional_digits: float_digits.map(|(_, scale)| scale),
+            // a column without any non-empty value gives no evidence for a hint.
+            semantic: if self.all_emails && self.min_len.is_some() {
+                Some(SemanticHint::Email)
+            } else if self.inference.is_hex_digest() {
+                Some(SemanticHint::HexDigest)
+            } else {
+                None
+            },
+            sample_values: self.sample_values,
+            confidence,
+            reasons,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(values: &[&str]) -> ColumnStats {
+        let options = InferenceOptions::default();
+        let mut collector = ColumnStatsCollector::new(&options);
+        for value in values {
+            collector.observe(value);
+        }
+        collector.finish("value")
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let stats = collect(&["b", "", "aaa", "b", "cc", "", "dddd", "e", "ff"]);
+        assert_eq!(
+            stats,
+            ColumnStats {
+                sql_type: SqlType::Varchar(4),
+                row_count: 9,
+                null_count: 2,
+                distinct_count: Some(6),
+                min_len: 1,
+                max_len: 4,
+                max_integer_digits: None,
+                max_fractional_digits: None,
+                sample_values: vec![
+                    "b".to_string(),
+                    "aaa".to_string(),
+                    "cc".to_string(),
+                    "dddd".to_string(),
+                    "e".to_string()
+                ],
+                semantic: None,
+                confidence: 1.0,
+                reasons: Vec::new(),
+            }
+        );
+
+        let empty = collect(&["", ""]);
+        assert_eq!(empty.distinct_count, Some(0));
+        assert_eq!(empty.min_len, 0);
+        assert!(empty.sample_values.is_empty());
+        assert_eq!(empty.confidence, 0.0);
+    }
+
+    #[test]
+    fn test_confidence_reasons() {
+        let flags = collect(&["1", "0", "0", "1", "1", "0"]);
+        assert_eq!(flags.sql_type, SqlType::Boolean);
+        assert_eq!(flags.reasons, vec!["boolean inferred from only 1/0"]);
+        assert_eq!(flags.confidence, 0.5);
+
+        assert!(
+            collect(&["true", "false", "0", "1", "yes"])
+                .reasons
+                .is_empty()
+        );
+
+        // a forced type is rated, not the inferred one
+        let options = InferenceOptions::default().with_type_override("flag", SqlType::Varchar(0));
+        let mut collector = ColumnStatsCollector::new(&options);
+        for value in ["1", "0", "0", "1", "1", "0"] {
+            collector.observe(value);
+        }
+        let forced = collector.finish("flag");
+        assert_eq!(forced.sql_type, SqlType::Varchar(1));
+        assert!(forced.reasons.is_empty());
+        assert_eq!(forced.confidence, 1.0);
+
+        let sparse = collect(&["", "", "", "42", "", "", "", "", "", ""]);
+        assert_eq!(sparse.sql_type, SqlType::Integer);
+        assert_eq!(sparse.reasons, vec!["90% null", "only 1 non-empty value"]);
+        assert!(sparse.confidence < 0.1);
+    }
+
+    #[test]
+    fn test_float_digits() {
+        let stats = collect(&["1.25", "", "3.1"]);
+        assert_eq!(stats.sql_type, SqlType::Float);
+        assert_eq!(stats.max_integer_digits, Some(1));
+        assert_eq!(stats.max_fractional_digits, Some(2));
+
+        let stats = collect(&["-120.5", "7", "1.5e3"]);
+        assert_eq!(stats.max_integer_digits, Some(3));
+        assert_eq!(stats.max_fractional_digits, Some(1));
+
+        // integers are no floats
+        assert_eq!(collect(&["1", "22"]).max_fractional_digits, None);
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let stats = collect(&["open", "closed", "", "open", "pending", "closed"]);
+        assert_eq!(
+            stats.enum_values(),
+            Some(
+                &[
+                    "open".to_string(),
+                    "closed".to_string(),
+                    "pending".to_string()
+                ][..]
+            )
+        );
+        // every value unique: an identifier, not an enum
+        assert_eq!(collect(&["a1", "b2", "c3"]).enum_values(), None);
+        // numbers are not text
+        assert_eq!(collect(&["1", "2", "1", "2"]).enum_values(), None);
+        assert_eq!(
+            collect(&["a", "b", "c", "d", "e", "f", "a"]).enum_values(),
+            None
+        );
+        assert_eq!(collect(&["", ""]).enum_values(), None);
+    }
+
+    #[test]
+    fn test_email_hint() {
+        let options = InferenceOptions {
+            detect_emails: true,
+            ..InferenceOptions::default()
+        };
+        let collect_with = |values: &[&str]| {
+            let mut collector = ColumnStatsCollector::new(&options);
+            for value in values {
+                collector.observe(value);
+            }
+            collector.finish("value")
+        };
+
+        let stats = collect_with(&["ann@example.com", "", "bob.smith+tag@mail.example.org"]);
+        assert_eq!(stats.semantic, Some(SemanticHint::Email));
+        assert_eq!(stats.sql_type, SqlType::Varchar(30));
+
+        assert_eq!(collect_with(&["ann@example.com", "n/a"]).semantic, None);
+        assert_eq!(collect_with(&["ann@localhost"]).semantic, None);
+        assert_eq!(collect_with(&["a b@example.com"]).semantic, None);
+        assert_eq!(collect_with(&["a@b@example.com"]).semantic, None);
+        assert_eq!(collect_with(&["ann@example.c0m"]).semantic, None);
+        assert_eq!(collect_with(&[""]).semantic, None);
+
+        // off by default
+        assert_eq!(collect(&["ann@example.com"]).semantic, None);
+    }
+
+    #[test]
+    fn test_hex_digest_hint() {
+        let options = InferenceOptions::default().with_hex_digest_detection(true);
+        let mut collector = ColumnStatsCollector::new(&options);
+        for value in [
+            "00000000000000000000000000000000",
+            "d41d8cd98f00b204e9800998ecf8427e",
+        ] {
+            collector.observe(value);
+        }
+        let stats = collector.finish("digest");
+        assert_eq!(stats.semantic, Some(SemanticHint::HexDigest));
+        assert_eq!(stats.sql_type, SqlType::Char(32));
+    }
+
+    #[test]
+    fn test_distinct_count_is_capped() {
+        let owned: Vec<String> = (0..=DISTINCT_LIMIT).map(|i| i.to_string()).collect();
+        let mut values: Vec<&str> = owned.iter().map(String::as_str).collect();
+        assert_eq!(
+            collect(&values[..DISTINCT_LIMIT]).distinct_count,
+            Some(DISTINCT_LIMIT)
+        );
+
+        values.push("0"); // repeats do not matter once the cap is hit
+        let stats = collect(&values);
+        assert_eq!(stats.distinct_count, None);
+        assert_eq!(stats.sql_type, SqlType::Integer);
+    }
+}
@@ -1,127 +1,997 @@
+use std::borrow::Cow;
+use std::net::IpAddr;
+use std::str::FromStr;
+
 use chrono::{NaiveDate, NaiveDateTime};
+use csv::StringRecord;
+
+use crate::{InferenceOptions, IntegerPolicy, column_value};
 
 const DATE_FORMAT: &str = "%Y-%m-%d";
 const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "SqlTypeRepr", try_from = "SqlTypeRepr"))]
 pub enum SqlType {
     Integer,
     BigInt,
     Float,
     Char(usize),
     Varchar(usize),
+    /// unbounded text. never inferred, but available to `InferenceOptions::type_overrides`.
+    Text,
+    /// a fixed-point number with `(precision, scale)`, inferred for currency amounts such as
+    /// `$1,200.00` when `InferenceOptions::detect_money` is enabled.
+    Decimal(usize, usize),
+    /// a float column whose values were written with a trailing `%` (e.g. `3.5%`), inferred
+    /// when `InferenceOptions::detect_percentages` is enabled. the number before the `%` is
+    /// the value, so `45%` is stored as `45`.
+    Percentage,
     Date,
     Boolean,
     Datetime,
+    /// an ipv4 or ipv6 address such as `192.168.0.1` or `::1`, inferred when
+    /// `InferenceOptions::detect_ip_addresses` is enabled.
+    Inet,
+    /// a column of json objects or arrays such as `{"a": 1}`, inferred when
+    /// `InferenceOptions::detect_json` is enabled (requires the `json` feature).
+    Json,
+}
+
+impl SqlType {
+    /// the lowercase name of the type in machine-readable output (see `generate_column_mapping`),
+    /// e.g. `varchar` for `Varchar(5)`. lengths, precision and scale are not part of it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SqlType::Integer => "integer",
+            SqlType::BigInt => "bigint",
+            SqlType::Float => "float",
+            SqlType::Char(_) => "char",
+            SqlType::Varchar(_) => "varchar",
+            SqlType::Text => "text",
+            SqlType::Decimal(..) => "decimal",
+            SqlType::Percentage => "percentage",
+            SqlType::Date => "date",
+            SqlType::Boolean => "boolean",
+            SqlType::Datetime => "datetime",
+            SqlType::Inet => "inet",
+            SqlType::Json => "json",
+        }
+    }
+
+    /// returns true if a column of this type can be changed to `other` without losing or
+    /// rejecting any value: integers widen to `BigInt`, then `Float`, decimals to more digits,
+    /// strings to a longer `Varchar`, dates to `Datetime`, and every type to `Text`.
+    pub fn widens_to(&self, other: &SqlType) -> bool {
+        match (self, other) {
+            _ if self == other => true,
+            (_, SqlType::Text) => true,
+            (SqlType::Integer, SqlType::BigInt | SqlType::Float) => true,
+            (SqlType::BigInt | SqlType::Decimal(..), SqlType::Float) => true,
+            // i32 has 10 digits and i64 19.
+            (SqlType::Integer, SqlType::Decimal(precision, scale)) => precision.saturating_sub(*scale) >= 10,
+            (SqlType::BigInt, SqlType::Decimal(precision, scale)) => precision.saturating_sub(*scale) >= 19,
+            (SqlType::Decimal(precision, scale), SqlType::Decimal(new_precision, new_scale)) => {
+                new_scale >= scale
+                    && new_precision.saturating_sub(*new_scale) >= precision.saturating_sub(*scale)
+            }
+            (SqlType::Char(len) | SqlType::Varchar(len), SqlType::Varchar(new_len)) => {
+                new_len >= len
+            }
+            (SqlType::Date, SqlType::Datetime) => true,
+            _ => false,
+        }
+    }
+}
+
+// with the `serde` feature, a `SqlType` (de)serializes as an object tagged by its `kind`, such as
+// `{"kind": "varchar", "length": 5}` or `{"kind": "decimal", "precision": 10, "scale": 2}`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SqlTypeRepr {
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scale: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SqlType> for SqlTypeRepr {
+    fn from(sql_type: SqlType) -> Self {
+        let (length, precision, scale) = match sql_type {
+            SqlType::Char(len) | SqlType::Varchar(len) => (Some(len), None, None),
+            SqlType::Decimal(precision, scale) => (None, Some(precision), Some(scale)),
+            _ => (None, None, None),
+        };
+        SqlTypeRepr { kind: sql_type.kind().to_string(), length, precision, scale }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SqlTypeRepr> for SqlType {
+    type Error = String;
+
+    fn try_from(repr: SqlTypeRepr) -> Result<Self, Self::Error> {
+        let missing = |field: &str| format!("sql type '{}' needs a {}", repr.kind, field);
+        match repr.kind.as_str() {
+            "char" => Ok(SqlType::Char(repr.length.ok_or_else(|| missing("length"))?)),
+            "varchar" => Ok(SqlType::Varchar(repr.length.ok_or_else(|| missing("length"))?)),
+            "decimal" => Ok(SqlType::Decimal(
+                repr.precision.ok_or_else(|| missing("precision"))?,
+                repr.scale.ok_or_else(|| missing("scale"))?,
+            )),
+            "integer" => Ok(SqlType::Integer),
+            "bigint" => Ok(SqlType::BigInt),
+            "float" => Ok(SqlType::Float),
+            "text" => Ok(SqlType::Text),
+            "percentage" => Ok(SqlType::Percentage),
+            "date" => Ok(SqlType::Date),
+            "boolean" => Ok(SqlType::Boolean),
+            "datetime" => Ok(SqlType::Datetime),
+            "inet" => Ok(SqlType::Inet),
+            "json" => Ok(SqlType::Json),
+            kind => Err(format!("unknown sql type kind '{}'", kind)),
+        }
+    }
+}
+
+/// parses a sql type name such as `INTEGER`, `VARCHAR(20)` or `DECIMAL(10, 2)`, case-insensitively.
+/// a `VARCHAR` or `CHAR` without a length parses with length 0, which
+/// `InferenceOptions::type_overrides` replaces by the length of the column's longest value.
+/// aliases such as `INT`, `DOUBLE PRECISION`, `TIMESTAMP` and `JSONB` are accepted.
+impl FromStr for SqlType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_uppercase();
+        let (name, args) = match upper.split_once('(') {
+            Some((name, rest)) => match rest.strip_suffix(')') {
+                Some(args) => (name.trim(), Some(args)),
+                None => return Err(format!("unterminated type arguments in '{}'", s)),
+            },
+            None => (upper.as_str(), None),
+        };
+        let lengths: Vec<usize> = match args {
+            Some(args) => args
+                .split(',')
+                .map(|arg| arg.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid type arguments in '{}'", s))?,
+            None => Vec::new(),
+        };
+
+        match (name, lengths.as_slice()) {
+            ("INTEGER" | "INT", []) => Ok(SqlType::Integer),
+            ("BIGINT", []) => Ok(SqlType::BigInt),
+            ("FLOAT" | "DOUBLE" | "DOUBLE PRECISION" | "REAL", []) => Ok(SqlType::Float),
+            ("CHAR", []) => Ok(SqlType::Char(0)),
+            ("CHAR", [len]) => Ok(SqlType::Char(*len)),
+            ("VARCHAR", []) => Ok(SqlType::Varchar(0)),
+            ("TEXT", []) => Ok(SqlType::Text),
+            ("VARCHAR", [len]) => Ok(SqlType::Varchar(*len)),
+            ("DECIMAL" | "NUMERIC", [precision, scale]) => Ok(SqlType::Decimal(*precision, *scale)),
+            ("PERCENTAGE", []) => Ok(SqlType::Percentage),
+            ("DATE", []) => Ok(SqlType::Date),
+            ("BOOLEAN" | "BOOL", []) => Ok(SqlType::Boolean),
+            ("DATETIME" | "TIMESTAMP", []) => Ok(SqlType::Datetime),
+            ("INET", []) => Ok(SqlType::Inet),
+            ("JSON" | "JSONB", []) => Ok(SqlType::Json),
+            _ => Err(format!("unknown sql type '{}'", s)),
+        }
+    }
+}
+
+/// the fewest hex digits a value needs to count towards `InferenceOptions::detect_hex_digests`,
+/// so that short numeric codes are never mistaken for digests.
+pub(crate) const HEX_DIGEST_MIN_LEN: usize = 16;
+
+// the values recognized as booleans unless configured otherwise.
+pub(crate) const DEFAULT_TRUE_VALUES: [&str; 3] = ["true", "t", "1"];
+pub(crate) const DEFAULT_FALSE_VALUES: [&str; 3] = ["false", "f", "0"];
+
+// strict membership: a value outside both sets (such as `maybe` among `yes`/`no`) is not a
+// boolean of either kind, so it makes the column fall through to the next type.
+fn is_boolean_value(value: &str, options: &InferenceOptions) -> bool {
+    let value = value.trim();
+    options
+        .true_values
+        .iter()
+        .chain(&options.false_values)
+        .any(|token| token.trim().eq_ignore_ascii_case(value))
+}
+
+// rewrites a numeric value into the form rust's number parsers accept. a configured thousands
+// separator is removed, but only if it groups the integer digits by three (`1,234,567`),
+// so arbitrary text containing the separator is never mistaken for a number. a decimal
+// separator other than `.` is replaced by `.`, and a `.` is then no longer accepted as one.
+// returns `None` for values that cannot be numbers under these rules.
+// surrounding whitespace, as kept by the csv reader in quoted fields such as `" 30"`, is
+// ignored: it decides nothing about the type, while lengths are still taken from the raw value.
+fn normalize_number<'v>(value: &'v str, options: &InferenceOptions) -> Option<Cow<'v, str>> {
+    normalize_number_with(value.trim(), options.decimal_separator, options.thousands_separator)
+}
+
+fn normalize_number_with(
+    value: &str,
+    decimal: char,
+    thousands: Option<char>,
+) -> Option<Cow<'_, str>> {
+    if decimal == '.' && thousands.is_none_or(|separator| !value.contains(separator)) {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let sign = &value[..value.len() - unsigned.len()];
+    let (integer_part, fraction_part) = match unsigned.split_once(decimal) {
+        Some((integer_part, fraction_part)) => (integer_part, Some(fraction_part)),
+        None => (unsigned, None),
+    };
+
+    let integer_part = match thousands {
+        Some(separator) if integer_part.contains(separator) => {
+            let is_digits = |group: &str| group.bytes().all(|b| b.is_ascii_digit());
+            let mut groups = integer_part.split(separator);
+            let first = groups.next().unwrap_or("");
+            let grouped = (1..=3).contains(&first.len())
+                && is_digits(first)
+                && groups.all(|group| group.len() == 3 && is_digits(group));
+            if !grouped {
+                return None;
+            }
+            Cow::Owned(integer_part.replace(separator, ""))
+        }
+        _ => Cow::Borrowed(integer_part),
+    };
+
+    let fraction_part = fraction_part.unwrap_or("");
+    if thousands.is_some_and(|separator| fraction_part.contains(separator))
+        || (decimal != '.' && (integer_part.contains('.') || fraction_part.contains('.')))
+    {
+        return None;
+    }
+
+    let mut normalized = format!("{}{}", sign, integer_part);
+    if unsigned.contains(decimal) {
+        normalized.push('.');
+        normalized.push_str(fraction_part);
+    }
+    Some(Cow::Owned(normalized))
+}
+
+// parses scientific notation such as `1E3` into the integer it denotes, if it has no
+// fractional part after expansion and `options.scientific_integers` is enabled.
+fn scientific_integer(value: &str, options: &InferenceOptions) -> Option<f64> {
+    if !options.scientific_integers || !value.contains(['e', 'E']) {
+        return None;
+    }
+    let parsed = value.parse::<f64>().ok()?;
+    (parsed.is_finite() && parsed.fract() == 0.0).then_some(parsed)
+}
+
+fn is_integer_value(value: &str, options: &InferenceOptions) -> bool {
+    let Some(value) = normalize_number(value, options) else {
+        return false;
+    };
+    let value = value.as_ref();
+    value.parse::<i32>().is_ok()
+        || scientific_integer(value, options)
+            .is_some_and(|n| n >= i32::MIN as f64 && n <= i32::MAX as f64)
+}
+
+fn is_bigint_value(value: &str, options: &InferenceOptions) -> bool {
+    // i64::MAX is not exactly representable as f64; the bound rounds up to 2^63, so the
+    // upper comparison is exclusive.
+    let Some(value) = normalize_number(value, options) else {
+        return false;
+    };
+    let value = value.as_ref();
+    value.parse::<i64>().is_ok()
+        || scientific_integer(value, options)
+            .is_some_and(|n| n >= i64::MIN as f64 && n < i64::MAX as f64)
+}
+
+fn is_float_value(value: &str, options: &InferenceOptions) -> bool {
+    float_digits(value, options).is_some()
+}
+
+// parses a float into the number of integer and fractional digits it was written with, e.g.
+// `-1,234.50` has 4 and 2. the digits of a mantissa count, not those after expansion, so
+// `1.5e3` has 1 and 1; `NaN` and `inf` have none.
+fn float_digits(value: &str, options: &InferenceOptions) -> Option<(usize, usize)> {
+    let normalized = normalize_number(value, options)?;
+    // rust happily parses `NaN`, `inf` and `infinity`, which in csv data are far more likely
+    // to be text than numbers, so they only count when explicitly allowed.
+    let f = normalized.parse::<f64>().ok()?;
+    if !f.is_finite() && !options.allow_non_finite_floats {
+        return None;
+    }
+    let mantissa = normalized.split(['e', 'E']).next().unwrap_or("");
+    let (integer_part, fraction_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let count_digits = |part: &str| part.bytes().filter(u8::is_ascii_digit).count();
+    Some((count_digits(integer_part), count_digits(fraction_part)))
+}
+
+// the currency symbols recognized by `detect_money` unless configured otherwise.
+pub(crate) const DEFAULT_CURRENCY_SYMBOLS: [&str; 4] = ["$", "€", "£", "¥"];
+
+// parses a currency amount such as `$1,200.00`, `-€9.99` or `12.50 €` into its number of
+// integer and fractional digits. exactly one configured currency symbol has to lead or
+// trail the amount, and thousands separators (`options.thousands_separator`, or `,` when
+// unset) must group the integer digits by three. a sign, `-` or `+` like for plain numbers,
+// may come before or after a leading symbol.
+fn money_digits(value: &str, options: &InferenceOptions) -> Option<(usize, usize)> {
+    if !options.detect_money {
+        return None;
+    }
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let amount = options.currency_symbols.iter().find_map(|symbol| {
+        unsigned
+            .strip_prefix(symbol.as_str())
+            .or_else(|| unsigned.strip_suffix(symbol.as_str()))
+    })?;
+    let amount = amount.trim();
+    let amount = amount.strip_prefix(['-', '+']).unwrap_or(amount); // `$-5.00`
+
+    let thousands = options.thousands_separator.unwrap_or(',');
+    let normalized = normalize_number_with(amount, options.decimal_separator, Some(thousands))?;
+    let (integer_digits, fraction_digits) = match normalized.split_once('.') {
+        Some((integer_digits, fraction_digits)) => (integer_digits, fraction_digits),
+        None => (normalized.as_ref(), ""),
+    };
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if integer_digits.is_empty() || !is_digits(integer_digits) || !is_digits(fraction_digits) {
+        return None;
+    }
+    Some((integer_digits.len(), fraction_digits.len()))
+}
+
+fn is_percentage_value(value: &str, options: &InferenceOptions) -> bool {
+    options.detect_percentages
+        && value
+            .strip_suffix('%')
+            .is_some_and(|number| is_float_value(number.trim_end(), options))
+}
+
+fn is_inet_value(value: &str, options: &InferenceOptions) -> bool {
+    options.detect_ip_addresses && IpAddr::from_str(value).is_ok()
+}
+
+#[cfg(feature = "json")]
+fn is_json_value(value: &str, options: &InferenceOptions) -> bool {
+    // bare scalars are rejected, otherwise every number or quoted string would count as json.
+    options.detect_json
+        && serde_json::from_str::<serde_json::Value>(value)
+            .is_ok_and(|json| json.is_object() || json.is_array())
+}
+
+#[cfg(not(feature = "json"))]
+fn is_json_value(_value: &str, _options: &InferenceOptions) -> bool {
+    false
+}
+
+fn is_datetime_value(value: &str, _options: &InferenceOptions) -> bool {
+    NaiveDateTime::parse_from_str(value, DATETIME_FORMAT).is_ok()
+}
+
+fn is_date_value(value: &str, _options: &InferenceOptions) -> bool {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).is_ok()
 }
 
 // infers the strictest possible sql type that can represent all non-empty string values in a column.
 // the function iterates through each value, attempting to parse it into several predefined types.
 // it maintains flags for whether all values encountered so far could fit into integer (i32),
-// bigint (i64), float (f64), boolean ("true", "false", "t", "f", "1", "0"), 
+// bigint (i64), float (f64), boolean (`InferenceOptions::true_values` and `false_values`),
 // date (yyyy-mm-dd), or datetime (yyyy-mm-dd hh:mm:ss).
 // empty strings ("") are skipped for type checking, effectively treating them as nullable.
 // if values are mixed such that no single specific type (other than varchar) applies to all 
 // non-empty values, the column will be inferred as varchar.
 //
 // the hierarchy for type determination, from strictest to most general, is:
-// 1. boolean: if all non-empty values are "true", "false", "t", "f", "1", "0" (case-insensitive).
-//    this means columns containing only "1"s and "0"s (and empty strings) will be typed as boolean.
+// 0. with `detect_hex_digests`, a column of same-length hex digests becomes char(n) first,
+//    so digests that happen to be all digits cannot make it numeric.
+// 1. boolean: if all non-empty values are "true", "false", "t", "f", "1", "0" (case-insensitive,
+//    or the configured `true_values` and `false_values`). this means columns containing only
+//    "1"s and "0"s (and empty strings) will be typed as boolean, but "-0" or "+1" will not.
 // 2. integer: if not boolean, and all non-empty values parse as i32.
 // 3. bigint: if not integer, and all non-empty values parse as i64.
-// 4. float: if not bigint, and all non-empty values parse as f64.
+//    integers may carry one leading sign, so "+30" and "-0" are integers.
+// 4. float: if not bigint, and all non-empty values parse as f64. signs are accepted like for
+//    integers, so "+1.5" and "-0.0" are floats (negative zero included).
+//    (with `detect_money`, currency amounts such as "$1,200.00" then become decimal(p, s),
+//    and with `detect_percentages`, values such as "3.5%" become percentage.)
 // 5. datetime: if not float, and all non-empty values parse as datetime ("%y-%m-%d %h:%m:%s").
 // 6. date: if not datetime, and all non-empty values parse as date ("%y-%m-%d").
+//    (with `detect_ip_addresses`, ipv4 and ipv6 addresses such as "10.0.0.1" then become inet,
+//    and with `detect_json`, json objects and arrays such as '{"a": 1}' become json.)
 // 7. char(n): if not any of the above, and all non-empty values have the exact same length n.
 // 8. varchar(n): otherwise, with length determined by the longest string encountered.
 // if a column is empty or contains only empty strings, it's inferred as varchar(0).
+// lengths are the byte length of the raw value, so newlines embedded in quoted fields count
+// towards varchar sizing like any other character (a `\r\n` counts two): the database stores
+// them, so a length without them would be too short to load the data. a value spanning several
+// lines is free text, so a column with one never becomes char(n), even if all lengths match.
 pub fn infer_sql_type(column_data: &[&str]) -> SqlType {
-    if column_data.is_empty() {
-        return SqlType::Varchar(0);
+    infer_sql_type_with_options(column_data, &InferenceOptions::default())
+}
+
+/// infers the type of a column like `infer_sql_type`, parsing values as configured by
+/// `options` (e.g. `scientific_integers`).
+pub fn infer_sql_type_with_options(column_data: &[&str], options: &InferenceOptions) -> SqlType {
+    infer_sql_type_iter_with_options(column_data.iter().copied(), options)
+}
+
+/// infers the type of a column like `infer_sql_type`, folding the values one at a time as the
+/// iterator yields them, so a lazily produced column never has to be collected into a slice.
+pub fn infer_sql_type_iter<'a>(values: impl IntoIterator<Item = &'a str>) -> SqlType {
+    infer_sql_type_iter_with_options(values, &InferenceOptions::default())
+}
+
+/// infers the type of a column like `infer_sql_type_iter`, parsing values as configured by
+/// `options`.
+pub fn infer_sql_type_iter_with_options<'a>(
+    values: impl IntoIterator<Item = &'a str>,
+    options: &InferenceOptions,
+) -> SqlType {
+    let mut state = ColumnInferenceState::new(options);
+    for value_str in values {
+        // null tokens are missing values, before any of the type checks see them.
+        state.observe(if options.is_null_token(value_str) { "" } else { value_str });
     }
+    state.finalize()
+}
 
-    let mut max_len = 0;
-    let mut first_non_empty_value_len: Option<usize> = None;
-    let mut all_non_empty_have_same_len = true;
-    let mut all_integers = true;
-    let mut all_bigints = true;
-    let mut all_floats = true;
-    let mut all_dates = true;
-    let mut all_datetimes = true;
-    let mut all_booleans = true;
-    let mut has_only_empty_strings = true; // track if all values encountered are empty
+/// the intermediate findings `infer_sql_type` decides a column's type from, for a custom type
+/// mapping that should not reimplement the value parsing. each `all_*` flag is true if every
+/// non-empty value parses as that type (as configured by the options, so a disabled type is
+/// always false); a column without non-empty values leaves the enabled ones vacuously true.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnFlags {
+    pub all_integers: bool,
+    pub all_bigints: bool,
+    pub all_floats: bool,
+    pub all_dates: bool,
+    pub all_datetimes: bool,
+    pub all_booleans: bool,
+    /// every non-empty value has the same length and none spans several lines.
+    pub all_same_len: bool,
+    /// the byte length of the longest value.
+    pub max_len: usize,
+    /// the number of empty (or null token) values.
+    pub null_count: usize,
+}
+
+/// computes the `ColumnFlags` of a column, with the default options.
+pub fn infer_column_flags(column_data: &[&str]) -> ColumnFlags {
+    infer_column_flags_with_options(column_data, &InferenceOptions::default())
+}
 
+/// computes the `ColumnFlags` of a column, parsing values as configured by `options`.
+pub fn infer_column_flags_with_options(
+    column_data: &[&str],
+    options: &InferenceOptions,
+) -> ColumnFlags {
+    let mut state = ColumnInferenceState::new(options);
     for value_str in column_data {
-        max_len = max_len.max(value_str.len());
+        state.observe(if options.is_null_token(value_str) { "" } else { value_str });
+    }
+    state.flags()
+}
+
+// the running state of `infer_sql_type` for one column. values are folded in one at a time
+// with `observe`, so a column can be inferred while its records are streamed, without ever
+// holding the whole column in memory. `finalize` applies the type hierarchy to what was seen.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnInferenceState<'a> {
+    options: &'a InferenceOptions,
+    max_len: usize,
+    first_non_empty_value_len: Option<usize>,
+    all_non_empty_have_same_len: bool,
+    all_integers: bool,
+    all_bigints: bool,
+    all_floats: bool,
+    all_dates: bool,
+    all_datetimes: bool,
+    all_booleans: bool,
+    all_money: bool,
+    all_percentages: bool,
+    all_inets: bool,
+    all_json: bool,
+    // true while every value is made of hex digits (and digest detection is enabled), and
+    // whether any of them used a letter, which tells a digest apart from a plain number.
+    all_hex: bool,
+    saw_hex_letter: bool,
+    // the most integer and fractional digits of any money value, for `Decimal(p, s)`.
+    money_integer_digits: usize,
+    money_scale: usize,
+    // the most integer and fractional digits of any float value, see `ColumnStats`.
+    float_integer_digits: usize,
+    float_scale: usize,
+    has_only_empty_strings: bool, // track if all values encountered are empty
+    value_count: usize,
+    empty_count: usize,
+    // the value that ruled out each candidate type (by its `explain` name), in the order the
+    // types were ruled out. only the first failing value of a type is kept.
+    disqualified_by: Vec<(&'static str, String)>,
+}
+
+impl<'a> ColumnInferenceState<'a> {
+    pub(crate) fn new(options: &'a InferenceOptions) -> Self {
+        ColumnInferenceState {
+            options,
+            max_len: 0,
+            first_non_empty_value_len: None,
+            all_non_empty_have_same_len: true,
+            all_integers: options.enabled_types.integer,
+            all_bigints: options.enabled_types.bigint,
+            all_floats: options.enabled_types.float,
+            all_dates: options.enabled_types.date,
+            all_datetimes: options.enabled_types.datetime,
+            all_booleans: options.enabled_types.boolean,
+            all_money: options.detect_money,
+            all_percentages: options.detect_percentages,
+            all_inets: options.detect_ip_addresses,
+            all_json: cfg!(feature = "json") && options.detect_json,
+            all_hex: options.detect_hex_digests,
+            saw_hex_letter: false,
+            money_integer_digits: 0,
+            money_scale: 0,
+            float_integer_digits: 0,
+            float_scale: 0,
+            has_only_empty_strings: true,
+            value_count: 0,
+            empty_count: 0,
+            disqualified_by: Vec::new(),
+        }
+    }
+
+    // folds one value of the column into the state.
+    pub(crate) fn observe(&mut self, value_str: &str) {
+        self.value_count += 1;
+        self.max_len = self.max_len.max(value_str.len());
 
         if value_str.is_empty() {
             // we allow nullable
-            continue;
+            self.empty_count += 1;
+            return;
+        }
+        self.has_only_empty_strings = false;
+
+        // once every check has failed the column can only be a varchar, whose length is already
+        // tracked above, so the (comparatively expensive) parsing below is skipped.
+        if self.is_settled_varchar() {
+            return;
         }
-        has_only_empty_strings = false;
 
         // check for consistent length among non-empty strings
-        if all_non_empty_have_same_len { // only update if still considered true
-            if let Some(len) = first_non_empty_value_len {
-                if value_str.len() != len {
-                    all_non_empty_have_same_len = false;
+        if self.all_non_empty_have_same_len { // only update if still considered true
+            let same_len = match self.first_non_empty_value_len {
+                _ if value_str.contains('\n') => false,
+                Some(len) => value_str.len() == len,
+                None => {
+                    self.first_non_empty_value_len = Some(value_str.len());
+                    true
                 }
-            } else {
-                first_non_empty_value_len = Some(value_str.len());
+            };
+            if !same_len {
+                self.all_non_empty_have_same_len = false;
+                self.disqualify("char", value_str);
             }
         }
 
-        if all_integers && value_str.parse::<i32>().is_err() {
-            all_integers = false;
+        if self.all_integers && !is_integer_value(value_str, self.options) {
+            self.all_integers = false;
+            self.disqualify("integer", value_str);
+        }
+        if self.all_bigints && !is_bigint_value(value_str, self.options) {
+            self.all_bigints = false;
+            self.disqualify("bigint", value_str);
         }
-        if all_bigints && value_str.parse::<i64>().is_err() {
-            all_bigints = false;
+        if self.all_floats {
+            match float_digits(value_str, self.options) {
+                Some((integer_digits, scale)) => {
+                    self.float_integer_digits = self.float_integer_digits.max(integer_digits);
+                    self.float_scale = self.float_scale.max(scale);
+                }
+                None => {
+                    self.all_floats = false;
+                    self.disqualify("float", value_str);
+                }
+            }
         }
-        if all_floats && value_str.parse::<f64>().is_err() {
-            all_floats = false;
+        if self.all_dates && !is_date_value(value_str, self.options) {
+            self.all_dates = false;
+            self.disqualify("date", value_str);
         }
-        if all_dates && NaiveDate::parse_from_str(value_str, DATE_FORMAT).is_err() {
-            all_dates = false;
+        if self.all_datetimes && !is_datetime_value(value_str, self.options) {
+            self.all_datetimes = false;
+            self.disqualify("datetime", value_str);
+        }
+        if self.all_booleans && !is_boolean_value(value_str, self.options) {
+            self.all_booleans = false;
+            self.disqualify("boolean", value_str);
+        }
+        if self.all_percentages && !is_percentage_value(value_str, self.options) {
+            self.all_percentages = false;
+            self.disqualify("percentage", value_str);
+        }
+        if self.all_inets && !is_inet_value(value_str, self.options) {
+            self.all_inets = false;
+            self.disqualify("inet", value_str);
+        }
+        if self.all_hex {
+            if value_str.bytes().all(|b| b.is_ascii_hexdigit()) {
+                self.saw_hex_letter |= value_str.bytes().any(|b| b.is_ascii_alphabetic());
+            } else {
+                self.all_hex = false;
+                self.disqualify("hex digest", value_str);
+            }
+        }
+        if self.all_json && !is_json_value(value_str, self.options) {
+            self.all_json = false;
+            self.disqualify("json", value_str);
+        }
+        if self.all_money {
+            match money_digits(value_str, self.options) {
+                Some((integer_digits, scale)) => {
+                    self.money_integer_digits = self.money_integer_digits.max(integer_digits);
+                    self.money_scale = self.money_scale.max(scale);
+                }
+                None => {
+                    self.all_money = false;
+                    self.disqualify("money", value_str);
+                }
+            }
+        }
+    }
+
+    fn disqualify(&mut self, name: &'static str, value_str: &str) {
+        self.disqualified_by.push((name, value_str.to_string()));
+    }
+
+    // ends the sample of `InferenceOptions::verify_tail`: from now on only the inferred type and
+    // the types it widens to are checked, so a violating value widens the type (at worst to a
+    // varchar) instead of reconsidering every type. a sample without values stays undecided.
+    // a boolean sampled from `1`/`0` keeps the number checks, so a later `2` makes it an integer.
+    pub(crate) fn start_verifying(&mut self) {
+        if self.has_only_empty_strings {
+            return;
+        }
+        let is_hex_digest = self.is_hex_digest();
+        let sampled = self.finalize();
+        let keep_numbers = matches!(sampled, SqlType::Integer | SqlType::BigInt | SqlType::Boolean);
+        self.all_booleans &= sampled == SqlType::Boolean;
+        self.all_integers &= matches!(sampled, SqlType::Integer | SqlType::Boolean);
+        self.all_bigints &= keep_numbers;
+        self.all_floats &= keep_numbers || matches!(sampled, SqlType::Float | SqlType::Decimal(..));
+        self.all_money &= matches!(sampled, SqlType::Decimal(..));
+        self.all_percentages &= sampled == SqlType::Percentage;
+        self.all_dates &= sampled == SqlType::Date;
+        self.all_datetimes &= sampled == SqlType::Datetime;
+        self.all_inets &= sampled == SqlType::Inet;
+        self.all_json &= sampled == SqlType::Json;
+        self.all_hex &= is_hex_digest;
+        self.all_non_empty_have_same_len &= matches!(sampled, SqlType::Char(_));
+    }
+
+    fn is_settled_varchar(&self) -> bool {
+        !(self.all_non_empty_have_same_len
+            || self.all_integers
+            || self.all_bigints
+            || self.all_floats
+            || self.all_dates
+            || self.all_datetimes
+            || self.all_booleans
+            || self.all_money
+            || self.all_percentages
+            || self.all_inets
+            || self.all_json)
+    }
+    // true if the column had no values at all, or at least one empty value (see `infer_nullable`).
+    pub(crate) fn is_nullable(&self) -> bool {
+        self.value_count == 0 || self.empty_count > 0
+    }
+    // true if the column looks like hex digests (md5, sha, ...): values of one length of at least
+    // `HEX_DIGEST_MIN_LEN` hex digits, at least one of them with a letter.
+    pub(crate) fn is_hex_digest(&self) -> bool {
+        self.all_hex
+            && self.saw_hex_letter
+            && self.all_non_empty_have_same_len
+            && self.first_non_empty_value_len.is_some_and(|len| len >= HEX_DIGEST_MIN_LEN)
+    }
+    pub(crate) fn value_count(&self) -> usize {
+        self.value_count
+    }
+    pub(crate) fn empty_count(&self) -> usize {
+        self.empty_count
+    }
+    pub(crate) fn max_len(&self) -> usize {
+        self.max_len
+    }
+    // the most integer and fractional digits of the values, while they are all floats.
+    pub(crate) fn float_digits(&self) -> Option<(usize, usize)> {
+        (self.all_floats && !self.has_only_empty_strings)
+            .then_some((self.float_integer_digits, self.float_scale))
+    }
+    pub(crate) fn flags(&self) -> ColumnFlags {
+        ColumnFlags {
+            all_integers: self.all_integers,
+            all_bigints: self.all_bigints,
+            all_floats: self.all_floats,
+            all_dates: self.all_dates,
+            all_datetimes: self.all_datetimes,
+            all_booleans: self.all_booleans,
+            all_same_len: self.all_non_empty_have_same_len,
+            max_len: self.max_len,
+            null_count: self.empty_count,
         }
-        if all_datetimes && NaiveDateTime::parse_from_str(value_str, DATETIME_FORMAT).is_err() {
-            all_datetimes = false;
+    }
+
+    // explains the type `finalize` picks, see `explain_sql_type`. the failing values were
+    // recorded while observing, so the column is never held in memory.
+    pub(crate) fn explain(&self) -> String {
+        let mut lines = Vec::new();
+        let reason = |name: &str| {
+            let value = self
+                .disqualified_by
+                .iter()
+                .find(|(disqualified, _)| *disqualified == name)
+                .map(|(_, value)| value.as_str());
+            match (name, value) {
+                ("char", Some(value)) if value.contains('\n') => {
+                    format!("value '{}' spans several lines", value.escape_debug())
+                }
+                ("char", Some(value)) => format!(
+                    "value '{}' has length {}, expected {}",
+                    value.escape_debug(),
+                    value.len(),
+                    self.first_non_empty_value_len.unwrap_or(0)
+                ),
+                (_, Some(value)) => format!("value '{}'", value.escape_debug()),
+                // ruled out by `start_verifying` rather than by a value.
+                (_, None) => "not the sampled type".to_string(),
+            }
+        };
+
+        if self.has_only_empty_strings {
+            lines.push("no non-empty values: every check is skipped".to_string());
+        } else {
+            let enabled = self.options.enabled_types;
+            // the candidate types in hierarchy order, each with whether it is considered at all.
+            let checks = [
+                (enabled.boolean, "boolean", self.all_booleans),
+                (enabled.integer, "integer", self.all_integers),
+                (enabled.bigint, "bigint", self.all_bigints),
+                (enabled.float, "float", self.all_floats),
+                (self.options.detect_money, "money", self.all_money),
+                (self.options.detect_percentages, "percentage", self.all_percentages),
+                (enabled.datetime, "datetime", self.all_datetimes),
+                (enabled.date, "date", self.all_dates),
+                (self.options.detect_ip_addresses, "inet", self.all_inets),
+                (cfg!(feature = "json") && self.options.detect_json, "json", self.all_json),
+            ];
+            // with `detect_hex_digests`, checked before the numeric types like in `finalize`.
+            let is_hex_digest = self.is_hex_digest();
+            let mut decided = is_hex_digest;
+            if self.options.detect_hex_digests {
+                if is_hex_digest {
+                    lines.push("hex digest: yes".to_string());
+                } else {
+                    let why = if !self.all_hex {
+                        reason("hex digest")
+                    } else if !self.saw_hex_letter {
+                        "no value has a hex letter".to_string()
+                    } else if !self.all_non_empty_have_same_len {
+                        "values differ in length".to_string()
+                    } else {
+                        format!("values are shorter than {} digits", HEX_DIGEST_MIN_LEN)
+                    };
+                    lines.push(format!("hex digest: no ({})", why));
+                }
+            }
+            for (_, name, passed) in checks
+                .into_iter()
+                .filter(|(is_enabled, _, _)| *is_enabled && !is_hex_digest)
+            {
+                if name == "integer" && self.options.integer_policy == IntegerPolicy::AlwaysBigInt {
+                    lines.push("integer: skipped (the integer policy widens to bigint)".to_string());
+                    continue;
+                }
+                if passed {
+                    lines.push(format!("{}: yes", name));
+                    decided = true;
+                    break;
+                }
+                lines.push(format!("{}: no ({})", name, reason(name)));
+            }
+
+            if !decided {
+                if self.options.prefer_varchar_over_char || !enabled.char {
+                    lines.push("char: skipped".to_string());
+                } else if self.all_non_empty_have_same_len {
+                    lines.push("char: yes".to_string());
+                } else {
+                    lines.push(format!("char: no ({})", reason("char")));
+                }
+            }
         }
-        if all_booleans {
-            let lower_val = value_str.to_lowercase();
-            if !matches!(lower_val.as_str(), "true" | "false" | "t" | "f" | "1" | "0") {
-                all_booleans = false;
+
+        lines.push(format!("result: {:?}", self.finalize()));
+        lines.join("\n")
+    }
+
+    // picks the strictest type that fits every value observed so far.
+    pub(crate) fn finalize(&self) -> SqlType {
+        if self.has_only_empty_strings {
+            // if the column had no data rows, or all of them were empty strings.
+            match &self.options.default_type_for_empty {
+                Some(sql_type) => sql_type.clone(),
+                // max_len will be 0 if all strings were indeed empty.
+                None => SqlType::Varchar(self.max_len),
             }
+        } else if self.is_hex_digest() {
+            // before the numeric checks: digests that happen to be all digits must not make the
+            // column numeric.
+            SqlType::Char(self.first_non_empty_value_len.unwrap_or(0))
+        } else if self.all_booleans { // check boolean first to ensure "1"s and "0"s become boolean
+            SqlType::Boolean
+        } else if self.all_integers && self.options.integer_policy == IntegerPolicy::Strict {
+            SqlType::Integer
+        } else if self.all_bigints {
+            SqlType::BigInt
+        } else if self.all_floats {
+            SqlType::Float
+        } else if self.all_money {
+            SqlType::Decimal(self.money_integer_digits + self.money_scale, self.money_scale)
+        } else if self.all_percentages {
+            SqlType::Percentage
+        } else if self.all_datetimes { // check datetime before date as datetime is more specific
+            SqlType::Datetime
+        } else if self.all_dates {
+            SqlType::Date
+        } else if self.all_inets {
+            SqlType::Inet
+        } else if self.all_json {
+            SqlType::Json
+        } else if self.all_non_empty_have_same_len
+            && self.options.enabled_types.char
+            && !self.options.prefer_varchar_over_char
+        {
+            // if all non-empty strings have the same length, and it's not a more specific type.
+            // first_non_empty_value_len is guaranteed to be some if !has_only_empty_strings
+            // and all_non_empty_have_same_len remained true.
+            SqlType::Char(self.first_non_empty_value_len.unwrap_or(0))
+        } else {
+            SqlType::Varchar(self.max_len)
         }
     }
+}
+
+/// an incremental schema inference: records are fed in with `observe_record`, from any number
+/// of batches or files, and `finalize` reports the types that fit every record seen so far.
+/// this keeps a schema refined over time without re-reading earlier input: a column that was
+/// integer in yesterday's file becomes a float once today's file has `2.5` in it.
+/// values are interpreted as configured by `options` (null tokens, separators, detections);
+/// type overrides are not applied, since the state does not know the column names.
+#[derive(Debug, Clone)]
+pub struct SchemaInferenceState<'a> {
+    options: &'a InferenceOptions,
+    columns: Vec<ColumnInferenceState<'a>>,
+}
 
-    if has_only_empty_strings {
-        // if the column had data rows, but all of them were empty strings.
-        SqlType::Varchar(max_len) // max_len will be 0 if all strings were indeed empty.
-    } else if all_booleans { // check boolean first to ensure "1"s and "0"s become boolean
-        SqlType::Boolean
-    } else if all_integers {
-        SqlType::Integer
-    } else if all_bigints {
-        SqlType::BigInt
-    } else if all_floats {
-        SqlType::Float
-    } else if all_datetimes { // check datetime before date as datetime is more specific
-        SqlType::Datetime
-    } else if all_dates {
-        SqlType::Date
-    } else if all_non_empty_have_same_len && !has_only_empty_strings {
-        // if all non-empty strings have the same length, and it's not a more specific type.
-        // first_non_empty_value_len is guaranteed to be some if !has_only_empty_strings
-        // and all_non_empty_have_same_len remained true.
-        SqlType::Char(first_non_empty_value_len.unwrap_or(0))
-    } else {
-        SqlType::Varchar(max_len)
+impl<'a> SchemaInferenceState<'a> {
+    /// creates the state for records of `num_columns` fields.
+    pub fn new(num_columns: usize, options: &'a InferenceOptions) -> Self {
+        SchemaInferenceState {
+            options,
+            columns: vec![ColumnInferenceState::new(options); num_columns],
+        }
+    }
+
+    /// folds one record into the state. missing trailing fields count as empty values and
+    /// extra fields are ignored, like in `InferenceOptions::flexible` mode.
+    pub fn observe_record(&mut self, record: &StringRecord) {
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.observe(column_value(record, i, self.options));
+        }
+    }
+
+    /// returns the number of records observed so far.
+    pub fn record_count(&self) -> usize {
+        self.columns.first().map_or(0, ColumnInferenceState::value_count)
+    }
+
+    /// returns the type of every column, as `infer_schema` would for all observed records.
+    pub fn finalize(&self) -> Vec<SqlType> {
+        self.columns.iter().map(ColumnInferenceState::finalize).collect()
+    }
+
+    /// returns whether every column is nullable, as `infer_schema_with_nullability` would.
+    pub fn nullability(&self) -> Vec<bool> {
+        self.columns.iter().map(ColumnInferenceState::is_nullable).collect()
     }
 }
 
+// explains how `infer_sql_type` arrives at the type of a column. each candidate type of the
+// hierarchy is checked in order against the non-empty values; a failed check names the first
+// value that disqualified it, and the walk stops at the first check that passes.
+// the returned text has one line per check, followed by a `result:` line.
+pub fn explain_sql_type(column_data: &[&str]) -> String {
+    explain_sql_type_with_options(column_data, &InferenceOptions::default())
+}
+
+/// explains the type of a column like `explain_sql_type`, parsing values as configured by
+/// `options`.
+pub fn explain_sql_type_with_options(column_data: &[&str], options: &InferenceOptions) -> String {
+    let mut state = ColumnInferenceState::new(options);
+    for value_str in column_data {
+        state.observe(if options.is_null_token(value_str) { "" } else { value_str });
+    }
+    state.explain()
+}
+
+// a column is nullable if any of its values is empty. a column without any values
+// is also reported as nullable, since nothing proves that it is always populated.
+pub fn infer_nullable(column_data: &[&str]) -> bool {
+    column_data.is_empty() || column_data.iter().any(|value| value.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::EnabledTypes;
+
+    #[test]
+    fn test_infer_column_flags_mixed_column() {
+        let flags = infer_column_flags(&["1", "2.5", "", "3", "NULL"]);
+        assert_eq!(
+            flags,
+            ColumnFlags {
+                all_integers: false,
+                all_bigints: false,
+                all_floats: false,
+                all_dates: false,
+                all_datetimes: false,
+                all_booleans: false,
+                all_same_len: false,
+                max_len: 4,
+                null_count: 1,
+            }
+        );
+        let options = InferenceOptions::default().with_null_tokens(["NULL"]);
+        let flags = infer_column_flags_with_options(&["1", "2.5", "", "3", "NULL"], &options);
+        assert!(flags.all_floats && !flags.all_integers && !flags.all_booleans);
+        assert_eq!((flags.max_len, flags.null_count), (3, 2));
+    }
+
+    #[test]
+    fn test_default_type_for_empty() {
+        let options =
+            InferenceOptions::default().with_default_type_for_empty(Some(SqlType::Integer));
+        assert_eq!(infer_sql_type_with_options(&["", ""], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&[], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&["", "ab"], &options), SqlType::Char(2));
+        assert_eq!(infer_sql_type(&["", ""]), SqlType::Varchar(0));
+    }
+
+    #[test]
+    fn test_widens_to() {
+        assert!(SqlType::Integer.widens_to(&SqlType::BigInt));
+        assert!(SqlType::BigInt.widens_to(&SqlType::Float));
+        assert!(SqlType::Varchar(5).widens_to(&SqlType::Varchar(8)));
+        assert!(SqlType::Char(3).widens_to(&SqlType::Varchar(3)));
+        assert!(SqlType::Decimal(6, 2).widens_to(&SqlType::Decimal(8, 3)));
+        assert!(SqlType::Date.widens_to(&SqlType::Datetime));
+        assert!(SqlType::Boolean.widens_to(&SqlType::Text));
+        assert!(!SqlType::BigInt.widens_to(&SqlType::Integer));
+        assert!(!SqlType::Float.widens_to(&SqlType::Integer));
+        assert!(!SqlType::Varchar(8).widens_to(&SqlType::Varchar(5)));
+        assert!(!SqlType::Decimal(6, 2).widens_to(&SqlType::Decimal(6, 3)));
+        assert!(!SqlType::Text.widens_to(&SqlType::Varchar(255)));
+    }
 
     #[test]
     fn test_infer_integer() {
@@ -261,6 +1131,490 @@ mod tests {
         assert_eq!(infer_sql_type(&["true", "false", "0", "four"]), SqlType::Varchar(5));
     }
 
+    #[test]
+    fn test_infer_configured_boolean_values() {
+        let options = InferenceOptions::default()
+            .with_boolean_values(["yes", "y"], ["no", "n"]);
+        assert_eq!(
+            infer_sql_type_with_options(&[" Yes ", "no", "YES", "", "N"], &options),
+            SqlType::Boolean
+        );
+        // `maybe` is neither true nor false, so the column is not boolean at all.
+        assert_eq!(
+            infer_sql_type_with_options(&["Yes", "No", "Maybe", "no"], &options),
+            SqlType::Varchar(5)
+        );
+        // the configured sets replace the default ones.
+        assert_eq!(infer_sql_type_with_options(&["true", "no"], &options), SqlType::Varchar(4));
+        assert_eq!(infer_sql_type(&["yes", "no"]), SqlType::Varchar(3));
+    }
+
+    #[test]
+    fn test_infer_multiline_values_as_varchar() {
+        // embedded newlines are part of the value: they count towards the length and
+        // prevent numeric/date parsing of the individual lines.
+        assert_eq!(infer_sql_type(&["line1\nline2", "short"]), SqlType::Varchar(11));
+        assert_eq!(infer_sql_type(&["1\n2", "30\n40"]), SqlType::Varchar(5));
+        assert_eq!(infer_sql_type(&["2023-01-01\n", "2023-01-02"]), SqlType::Varchar(11));
+        // same lengths, but multiline text is not a fixed-width code.
+        assert_eq!(infer_sql_type(&["ab\ncd", "efghi"]), SqlType::Varchar(5));
+        assert_eq!(infer_sql_type(&["a\r\nb", "c\r\nd"]), SqlType::Varchar(4));
+        assert!(explain_sql_type(&["ab\ncd", "efghi"]).contains("char: no (value 'ab\\ncd' spans several lines)"));
+    }
+
+    #[test]
+    fn test_explain_names_disqualifying_values() {
+        let explanation = explain_sql_type(&["1", "3.5", "", "2"]);
+        assert_eq!(
+            explanation,
+            "boolean: no (value '3.5')\n\
+             integer: no (value '3.5')\n\
+             bigint: no (value '3.5')\n\
+             float: yes\n\
+             result: Float"
+        );
+    }
+
+    #[test]
+    fn test_explain_falls_through_to_varchar() {
+        let explanation = explain_sql_type(&["maybe", "no"]);
+        assert!(explanation.starts_with("boolean: no (value 'maybe')\n"));
+        assert!(explanation.contains("date: no (value 'maybe')"));
+        assert!(explanation.contains("char: no (value 'no' has length 2, expected 5)"));
+        assert!(explanation.ends_with("result: Varchar(5)"));
+
+        assert_eq!(
+            explain_sql_type(&["", ""]),
+            "no non-empty values: every check is skipped\nresult: Varchar(0)"
+        );
+    }
+
+    #[test]
+    fn test_explain_after_verifying() {
+        let options = InferenceOptions::default();
+        let mut state = ColumnInferenceState::new(&options);
+        state.observe("2024-01-01");
+        state.start_verifying();
+        state.observe("2024-01-01 10:00:00");
+        let explanation = state.explain();
+        assert!(explanation.contains("\ndatetime: no (value '2024-01-01')\n"));
+        assert!(explanation.contains("\ndate: no (value '2024-01-01 10:00:00')\n"));
+        assert!(explanation.ends_with("char: no (not the sampled type)\nresult: Varchar(19)"));
+    }
+
+    #[test]
+    fn test_infer_nullable() {
+        assert!(!infer_nullable(&["1", "2"]));
+        assert!(infer_nullable(&["1", ""]));
+        assert!(infer_nullable(&["", ""]));
+        assert!(infer_nullable(&[])); // no values at all, so nothing proves non-null
+    }
+
+    #[test]
+    fn test_infer_varchar_early_exit_keeps_tracking_length() {
+        use std::time::Instant;
+
+        // the first two values already rule out every type but varchar.
+        let mut owned = vec!["a".to_string(), "bc".to_string()];
+        for i in 0..200_000 {
+            owned.push(format!("text value {}", i));
+        }
+        owned.push("x".repeat(100)); // the longest value comes last
+        owned.push(String::new());
+        let column_data: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        let start_time = Instant::now();
+        let sql_type = infer_sql_type(&column_data);
+        println!("\ninferred {} varchar values in {:?}", column_data.len(), start_time.elapsed());
+
+        assert_eq!(sql_type, SqlType::Varchar(100));
+        assert!(infer_nullable(&column_data));
+        assert_eq!(infer_sql_type(&["a", "bc", "123"]), SqlType::Varchar(3));
+    }
+
+    #[test]
+    fn test_infer_scientific_integers() {
+        let options = InferenceOptions::default().with_scientific_integers(true);
+        assert_eq!(infer_sql_type_with_options(&["1E3", "42"], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&["5e12"], &options), SqlType::BigInt);
+        // 1.5E1 expands to 15, which has no fractional part.
+        assert_eq!(infer_sql_type_with_options(&["1.5E1"], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&["1.25E1", "3"], &options), SqlType::Float);
+        assert_eq!(infer_sql_type_with_options(&["1E30"], &options), SqlType::Float);
+        // plain decimals are not scientific notation, even when integral.
+        assert_eq!(infer_sql_type_with_options(&["2.0"], &options), SqlType::Float);
+
+        // off by default
+        assert_eq!(infer_sql_type(&["1E3", "42"]), SqlType::Float);
+    }
+
+    #[test]
+    fn test_infer_nan_and_infinity() {
+        assert_eq!(infer_sql_type(&["1.25", "NaN"]), SqlType::Varchar(4));
+        assert_eq!(infer_sql_type(&["inf", "-Infinity"]), SqlType::Varchar(9));
+        assert_eq!(infer_sql_type(&["1.0", "-2.5e3"]), SqlType::Float);
+
+        let options = InferenceOptions {
+            allow_non_finite_floats: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["1.0", "NaN", "inf"], &options), SqlType::Float);
+    }
+
+    #[test]
+    fn test_infer_thousands_separator() {
+        let options = InferenceOptions {
+            thousands_separator: Some(','),
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["1,234", "-12,345,678"], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&["1,234.50", "999"], &options), SqlType::Float);
+        assert_eq!(infer_sql_type_with_options(&["3,000,000,000"], &options), SqlType::BigInt);
+        // misplaced separators are not digit grouping
+        assert_eq!(infer_sql_type_with_options(&["12,34", "1"], &options), SqlType::Varchar(5));
+        assert_eq!(infer_sql_type_with_options(&["1234,567", "1"], &options), SqlType::Varchar(8));
+        assert_eq!(infer_sql_type_with_options(&[",123", "1"], &options), SqlType::Varchar(4));
+
+        // off by default
+        assert_eq!(infer_sql_type(&["1,234", "5,678"]), SqlType::Char(5));
+    }
+
+    #[test]
+    fn test_infer_decimal_comma() {
+        let options = InferenceOptions {
+            decimal_separator: ',',
+            thousands_separator: Some('.'),
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["3,14", "-2,0"], &options), SqlType::Float);
+        assert_eq!(infer_sql_type_with_options(&["1.234,5", "7"], &options), SqlType::Float);
+        assert_eq!(infer_sql_type_with_options(&["1.234", "12"], &options), SqlType::Integer);
+        // a `.` is not a decimal point in this locale
+        assert_eq!(infer_sql_type_with_options(&["3.14", "2"], &options), SqlType::Varchar(4));
+    }
+
+    #[test]
+    fn test_infer_money() {
+        let options = InferenceOptions {
+            detect_money: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(
+            infer_sql_type_with_options(&["$1,200.00", "$5", "", "-$0.5"], &options),
+            SqlType::Decimal(6, 2)
+        );
+        assert_eq!(infer_sql_type_with_options(&["€9.99", "12.50 €"], &options), SqlType::Decimal(4, 2));
+        // a value without a currency symbol or with bad grouping degrades to text
+        assert_eq!(infer_sql_type_with_options(&["$1,200.00", "1200"], &options), SqlType::Varchar(9));
+        assert_eq!(infer_sql_type_with_options(&["$1,20.00", "$3"], &options), SqlType::Varchar(8));
+        assert_eq!(infer_sql_type_with_options(&["$1.00", "hello!"], &options), SqlType::Varchar(6));
+        // plain numbers keep their numeric types
+        assert_eq!(infer_sql_type_with_options(&["1.5", "2"], &options), SqlType::Float);
+
+        // off by default
+        assert_eq!(infer_sql_type(&["$1,200.00", "$5"]), SqlType::Varchar(9));
+    }
+
+    #[test]
+    fn test_infer_signed_numbers() {
+        assert_eq!(infer_sql_type(&["+1", "+2"]), SqlType::Integer);
+        assert_eq!(infer_sql_type(&["-0", "+0", "7"]), SqlType::Integer);
+        // a signed zero is not one of the boolean values
+        assert_eq!(infer_sql_type(&["1", "0", "-0"]), SqlType::Integer);
+        assert_eq!(infer_sql_type(&["+3000000000", "-1"]), SqlType::BigInt);
+        assert_eq!(infer_sql_type(&["+1.5", "-0.0"]), SqlType::Float);
+        assert_eq!(infer_sql_type(&["-0.0", "0"]), SqlType::Float);
+        assert_eq!(infer_sql_type(&["+.5", "-5."]), SqlType::Float);
+        // a sign needs digits, and only one sign is allowed
+        assert_eq!(infer_sql_type(&["+", "-"]), SqlType::Char(1));
+        assert_eq!(infer_sql_type(&["+-1", "--1"]), SqlType::Char(3));
+
+        let options = InferenceOptions {
+            thousands_separator: Some(','),
+            detect_money: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["+1,234", "-5"], &options), SqlType::Integer);
+        assert_eq!(
+            infer_sql_type_with_options(&["+$5.00", "$+1.25", "-$3"], &options),
+            SqlType::Decimal(3, 2)
+        );
+    }
+
+    #[test]
+    fn test_sql_type_from_str() {
+        assert_eq!("integer".parse(), Ok(SqlType::Integer));
+        assert_eq!("VARCHAR(20)".parse(), Ok(SqlType::Varchar(20)));
+        assert_eq!("text".parse(), Ok(SqlType::Text));
+        assert_eq!("varchar".parse(), Ok(SqlType::Varchar(0)));
+        assert_eq!("decimal(10, 2)".parse(), Ok(SqlType::Decimal(10, 2)));
+        assert_eq!("double precision".parse(), Ok(SqlType::Float));
+        assert_eq!("timestamp".parse(), Ok(SqlType::Datetime));
+        assert!("varchar(abc)".parse::<SqlType>().is_err());
+        assert!("decimal(10".parse::<SqlType>().is_err());
+        assert!("integer(4)".parse::<SqlType>().is_err());
+        assert!("blob".parse::<SqlType>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_sql_type_serde_round_trip() {
+        let json = serde_json::to_string(&SqlType::Varchar(5)).unwrap();
+        assert_eq!(json, r#"{"kind":"varchar","length":5}"#);
+        assert_eq!(serde_json::to_string(&SqlType::Integer).unwrap(), r#"{"kind":"integer"}"#);
+
+        for sql_type in [SqlType::Varchar(5), SqlType::Decimal(10, 2), SqlType::Datetime, SqlType::Char(3)] {
+            let json = serde_json::to_string(&sql_type).unwrap();
+            assert_eq!(serde_json::from_str::<SqlType>(&json).unwrap(), sql_type);
+        }
+        assert!(serde_json::from_str::<SqlType>(r#"{"kind":"varchar"}"#).is_err());
+        assert!(serde_json::from_str::<SqlType>(r#"{"kind":"blob"}"#).is_err());
+    }
+
+    #[test]
+    fn test_explain_hex_digests_and_integer_policy() {
+        let options = InferenceOptions {
+            detect_hex_digests: true,
+            ..InferenceOptions::default()
+        };
+        let digests = ["d41d8cd98f00b204e9800998ecf8427e", "00000000000000000000000000000000"];
+        assert_eq!(
+            explain_sql_type_with_options(&digests, &options),
+            "hex digest: yes\nresult: Char(32)"
+        );
+        assert_eq!(
+            explain_sql_type_with_options(&["1234567890123456"], &options),
+            "hex digest: no (no value has a hex letter)\nboolean: no (value '1234567890123456')\n\
+             integer: no (value '1234567890123456')\nbigint: yes\nresult: BigInt"
+        );
+
+        let options = InferenceOptions::default().with_integer_policy(IntegerPolicy::AlwaysBigInt);
+        assert_eq!(
+            explain_sql_type_with_options(&["12", "34"], &options),
+            "boolean: no (value '12')\ninteger: skipped (the integer policy widens to bigint)\n\
+             bigint: yes\nresult: BigInt"
+        );
+    }
+
+    #[test]
+    fn test_infer_hex_digests() {
+        let options = InferenceOptions {
+            detect_hex_digests: true,
+            ..InferenceOptions::default()
+        };
+        let digests = ["00000000000000000000000000000000", "d41d8cd98f00b204e9800998ecf8427e", ""];
+        assert_eq!(infer_sql_type_with_options(&digests, &options), SqlType::Char(32));
+        // only digits stay numeric, e.g. ids that happen to be long
+        let digits = ["1234567890123456", "6543210987654321"];
+        assert_eq!(infer_sql_type_with_options(&digits, &options), SqlType::BigInt);
+        // too short or of different lengths is not a digest
+        assert_eq!(infer_sql_type_with_options(&["1a", "20"], &options), SqlType::Char(2));
+        assert_eq!(infer_sql_type_with_options(&["12345678901234567890123456789012", "d41d8cd98f00b204e9800998ecf8427e0"], &options), SqlType::Varchar(33));
+
+        // without detection an all-digit digest collapses the column to a float
+        assert_eq!(infer_sql_type(&["12345678901234567890123456789012", "98765432109876543210987654321098"]), SqlType::Float);
+        assert_eq!(infer_sql_type(&digests), SqlType::Char(32));
+    }
+
+    #[test]
+    fn test_infer_always_bigint() {
+        let options = InferenceOptions::default().with_integer_policy(IntegerPolicy::AlwaysBigInt);
+        assert_eq!(infer_sql_type_with_options(&["1", "2"], &options), SqlType::BigInt);
+        assert_eq!(infer_sql_type_with_options(&["1", "3000000000"], &options), SqlType::BigInt);
+        // booleans and floats are not affected
+        assert_eq!(infer_sql_type_with_options(&["1", "0"], &options), SqlType::Boolean);
+        assert_eq!(infer_sql_type_with_options(&["1", "2.5"], &options), SqlType::Float);
+
+        assert_eq!(infer_sql_type(&["1", "2"]), SqlType::Integer);
+    }
+
+    #[test]
+    fn test_infer_numbers_with_surrounding_spaces() {
+        assert_eq!(infer_sql_type(&[" 30", " 24"]), SqlType::Integer);
+        assert_eq!(infer_sql_type(&["1.5 ", "2"]), SqlType::Float);
+        assert_eq!(infer_sql_type(&[" ", "1"]), SqlType::Char(1)); // blank is not a number
+
+        // the raw value, spaces included, still sizes a text column
+        let options = InferenceOptions::default();
+        let mut state = ColumnInferenceState::new(&options);
+        state.observe(" 30");
+        state.observe(" 24");
+        assert_eq!(state.max_len(), 3);
+        assert_eq!(infer_sql_type(&[" 30", "abc"]), SqlType::Char(3));
+    }
+
+    #[test]
+    fn test_disabled_boolean_check() {
+        let options = InferenceOptions::default()
+            .with_enabled_types(EnabledTypes { boolean: false, ..EnabledTypes::default() });
+        assert_eq!(infer_sql_type(&["1", "0", "1"]), SqlType::Boolean);
+        assert_eq!(infer_sql_type_with_options(&["1", "0", "1"], &options), SqlType::Integer);
+        assert_eq!(infer_sql_type_with_options(&["true", "false"], &options), SqlType::Varchar(5));
+        assert!(!explain_sql_type_with_options(&["1", "0"], &options).contains("boolean"));
+    }
+
+    #[test]
+    fn test_disabled_date_check() {
+        let options = InferenceOptions::default()
+            .with_enabled_types(EnabledTypes { date: false, ..EnabledTypes::default() });
+        let dates = ["2024-01-15", "2023-12-31"];
+        assert_eq!(infer_sql_type(&dates), SqlType::Date);
+        assert_eq!(infer_sql_type_with_options(&dates, &options), SqlType::Char(10));
+        // the other checks still run
+        assert_eq!(infer_sql_type_with_options(&["1", "0"], &options), SqlType::Boolean);
+    }
+
+    #[test]
+    fn test_explain_with_disabled_types_and_detections() {
+        let options = InferenceOptions {
+            detect_money: true,
+            detect_percentages: true,
+            enabled_types: EnabledTypes {
+                boolean: false,
+                integer: false,
+                bigint: false,
+                float: false,
+                date: false,
+                datetime: false,
+                ..EnabledTypes::default()
+            },
+            ..InferenceOptions::default()
+        };
+        let explanation = explain_sql_type_with_options(&["$1.50", "$20.00"], &options);
+        assert_eq!(explanation, "money: yes\nresult: Decimal(4, 2)");
+
+        // with only boolean disabled, money is still checked right after float.
+        let options = InferenceOptions {
+            enabled_types: EnabledTypes { boolean: false, ..EnabledTypes::default() },
+            ..options
+        };
+        let explanation = explain_sql_type_with_options(&["12%", "abc"], &options);
+        let checks: Vec<&str> = explanation
+            .lines()
+            .map(|line| line.split(':').next().unwrap_or(""))
+            .collect();
+        assert_eq!(
+            checks,
+            vec!["integer", "bigint", "float", "money", "percentage", "datetime", "date", "char", "result"]
+        );
+    }
+
+    #[test]
+    fn test_null_placeholders_with_integers() {
+        let options = InferenceOptions::default().with_null_tokens(["#N/A", "(null)"]);
+        let column = ["1", "#N/A", "42", "(NULL)", " #n/a "];
+        assert_eq!(infer_sql_type_with_options(&column, &options), SqlType::Integer);
+        assert_eq!(infer_sql_type(&column), SqlType::Varchar(6));
+        // only placeholders: no evidence for any type, and no length either
+        assert_eq!(
+            infer_sql_type_with_options(&["#N/A", "#N/A"], &options),
+            SqlType::Varchar(0)
+        );
+        assert!(explain_sql_type_with_options(&column, &options).contains("integer: yes"));
+    }
+
+    #[test]
+    fn test_schema_inference_state_across_batches() {
+        let options = InferenceOptions::default().with_null_tokens(["NA"]);
+        let mut state = SchemaInferenceState::new(3, &options);
+        for record in [vec!["1", "a", "2024-01-01"], vec!["20", "b", "NA"]] {
+            state.observe_record(&StringRecord::from(record));
+        }
+        assert_eq!(state.finalize(), vec![SqlType::Integer, SqlType::Char(1), SqlType::Date]);
+
+        // a later batch (e.g. the next day's file) widens the types.
+        for record in [vec!["2.5", "ccc"], vec!["3", "d", "2024-01-02"]] {
+            state.observe_record(&StringRecord::from(record));
+        }
+        assert_eq!(state.finalize(), vec![SqlType::Float, SqlType::Varchar(3), SqlType::Date]);
+        assert_eq!(state.nullability(), vec![false, false, true]);
+        assert_eq!(state.record_count(), 4);
+    }
+
+    #[test]
+    fn test_infer_sql_type_iter_matches_slice() {
+        let columns: [&[&str]; 6] = [
+            &["1", "2", "300"],
+            &["1", "0", ""],
+            &["1.5", "2", "-3e2"],
+            &["2024-01-01", "", "2023-12-31"],
+            &["abc", "de", ""],
+            &[],
+        ];
+        for column in columns {
+            assert_eq!(infer_sql_type_iter(column.iter().copied()), infer_sql_type(column));
+        }
+
+        // a lazily split line, never collected.
+        let line = String::from("10;20;;30");
+        assert_eq!(infer_sql_type_iter(line.split(';')), SqlType::Integer);
+        let options = InferenceOptions::default().with_null_tokens(["-"]);
+        assert_eq!(
+            infer_sql_type_iter_with_options("4 - 5".split(' '), &options),
+            SqlType::Integer
+        );
+    }
+
+    #[test]
+    fn test_prefer_varchar_over_char() {
+        let options = InferenceOptions::default().with_prefer_varchar_over_char(true);
+        assert_eq!(infer_sql_type_with_options(&["abc", "def"], &options), SqlType::Varchar(3));
+        assert_eq!(infer_sql_type(&["abc", "def"]), SqlType::Char(3));
+        // typed columns are unaffected
+        assert_eq!(infer_sql_type_with_options(&["123", "456"], &options), SqlType::Integer);
+        assert!(explain_sql_type_with_options(&["abc", "def"], &options).contains("char: skipped"));
+    }
+
+    #[test]
+    fn test_infer_ip_addresses() {
+        let options = InferenceOptions {
+            detect_ip_addresses: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["192.168.0.1", "", "10.0.0.254"], &options), SqlType::Inet);
+        // ipv4 and ipv6 mix
+        assert_eq!(infer_sql_type_with_options(&["127.0.0.1", "::1", "2001:db8::8a2e:370:7334"], &options), SqlType::Inet);
+        assert_eq!(infer_sql_type_with_options(&["10.0.0.1", "gateway"], &options), SqlType::Varchar(8));
+        assert_eq!(infer_sql_type_with_options(&["256.0.0.1"], &options), SqlType::Char(9));
+
+        // off by default
+        assert_eq!(infer_sql_type(&["10.0.0.1", "::1"]), SqlType::Varchar(8));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_infer_json() {
+        let options = InferenceOptions {
+            detect_json: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["{\"a\":1}", "{\"b\":2}"], &options), SqlType::Json);
+        assert_eq!(infer_sql_type_with_options(&["[1, 2]", "", "{\"a\": [true]}"], &options), SqlType::Json);
+        // bare scalars are valid json, but not json columns
+        assert_eq!(infer_sql_type_with_options(&["1", "2.5"], &options), SqlType::Float);
+        assert_eq!(infer_sql_type_with_options(&["\"a\"", "null"], &options), SqlType::Varchar(4));
+        assert_eq!(infer_sql_type_with_options(&["{\"a\":1}", "{oops}"], &options), SqlType::Varchar(7));
+
+        // off by default
+        assert_eq!(infer_sql_type(&["{\"a\":1}", "{\"b\":2}"]), SqlType::Char(7));
+    }
+
+    #[test]
+    fn test_infer_percentages() {
+        let options = InferenceOptions {
+            detect_percentages: true,
+            ..InferenceOptions::default()
+        };
+        assert_eq!(infer_sql_type_with_options(&["45%", "3.5%", ""], &options), SqlType::Percentage);
+        assert_eq!(infer_sql_type_with_options(&["45%", "hello"], &options), SqlType::Varchar(5));
+        // every value needs the `%`
+        assert_eq!(infer_sql_type_with_options(&["45%", "12"], &options), SqlType::Varchar(3));
+        assert_eq!(infer_sql_type_with_options(&["%", "abc%"], &options), SqlType::Varchar(4));
+
+        // off by default
+        assert_eq!(infer_sql_type(&["45%", "3.5%"]), SqlType::Varchar(4));
+    }
+
     #[test]
     fn test_infer_invalid_date_as_varchar() {
         assert_eq!(infer_sql_type(&["2023-13-01"]), SqlType::Char(10)); // invalid month
@@ -0,0 +1,117 @@
+use std::io::{self, Write};
+
+use super::{SqlType, StringRecord};
+use crate::utils::{to_camel_case, to_pascal_case, unique_names, write_to_string};
+
+/// generates a typescript interface from an interface name, headers, and inferred types.
+/// columns whose `nullability` entry is true are typed as `T | null`.
+pub fn generate_typescript(
+    interface_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> String {
+    write_to_string(|writer| {
+        generate_typescript_to(writer, interface_name, headers, types, nullability)
+    })
+}
+
+/// writes the interface of `generate_typescript` to `writer`.
+pub fn generate_typescript_to<W: Write>(
+    writer: &mut W,
+    interface_name: &str,
+    headers: &StringRecord,
+    types: &[SqlType],
+    nullability: &[bool],
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "export interface {} {{",
+        to_pascal_case(interface_name)
+    )?;
+
+    // repeated headers (`id, name, id`) would declare the same property twice.
+    let property_names = unique_names(headers.iter().map(to_camel_case));
+    for (i, property_name) in property_names.iter().enumerate() {
+        // dates and datetimes travel as iso 8601 strings.
+        let ts_type = match &types[i] {
+            SqlType::Integer
+            | SqlType::BigInt
+            | SqlType::Float
+            | SqlType::Decimal(..)
+            | SqlType::Percentage => "number",
+            SqlType::Boolean => "boolean",
+            SqlType::Date | SqlType::Datetime => "string",
+            SqlType::Char(_) | SqlType::Varchar(_) | SqlType::Text | SqlType::Inet => "string",
+            SqlType::Json => "unknown",
+        };
+        let null_suffix = if nullability[i] { " | null" } else { "" };
+        writeln!(writer, "  {}: {}{};", property_name, ts_type, null_suffix)?;
+    }
+
+    writer.write_all(b"}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_typescript_maps_every_type() {
+        let headers = StringRecord::from(vec![
+            "id",
+            "big_count",
+            "unit price",
+            "code",
+            "description",
+            "start_date",
+            "updated-at",
+            "is_active",
+        ]);
+        let types = vec![
+            SqlType::Integer,
+            SqlType::BigInt,
+            SqlType::Float,
+            SqlType::Char(3),
+            SqlType::Varchar(40),
+            SqlType::Date,
+            SqlType::Datetime,
+            SqlType::Boolean,
+        ];
+        let nullability = vec![false, true, true, false, true, true, false, true];
+        let expected = "export interface OrderLines {
+  id: number;
+  bigCount: number | null;
+  unitPrice: number | null;
+  code: string;
+  description: string | null;
+  startDate: string | null;
+  updatedAt: string;
+  isActive: boolean | null;
+}";
+        assert_eq!(
+            generate_typescript("order_lines", &headers, &types, &nullability),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_repeated_headers() {
+        let headers = StringRecord::from(vec!["id", "name", "id"]);
+        let types = vec![SqlType::Integer, SqlType::Text, SqlType::Integer];
+        assert_eq!(
+            generate_typescript("people", &headers, &types, &[false; 3]),
+            "export interface People {\n  id: number;\n  name: string;\n  id_2: number;\n}"
+        );
+    }
+
+    #[test]
+    fn test_generate_typescript_empty_columns() {
+        let headers = StringRecord::new();
+        assert_eq!(
+            generate_typescript("empty", &headers, &[], &[]),
+            "export interface Empty {\n}"
+        );
+    }
+}
@@ -1,8 +1,185 @@
-/// converts a string from snake_case or kebab-case to pascalcase.
+use std::collections::HashSet;
+use std::io;
+
+/// runs a `*_to` generator against an in-memory buffer and returns what it wrote.
+/// this is how the `String`-returning generators delegate to their writer variants.
+pub fn write_to_string<F>(generate: F) -> String
+where
+    F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+{
+    let mut buffer = Vec::new();
+    generate(&mut buffer).expect("writing to a Vec<u8> cannot fail");
+    String::from_utf8(buffer).expect("generators only write utf-8")
+}
+
+/// converts a string from snake_case, kebab-case or space separated words to pascalcase, for use
+/// as a class or type name. any character that is not alphanumeric separates words and is
+/// dropped, and a name that would start with a digit (or be empty) gets a `T` prefix, so
+/// `2023 sales!` becomes `T2023Sales`.
 pub fn to_pascal_case(s: &str) -> String {
-    s.split(|c: char| c == '_' || c == '-')
+    let name: String = s
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.as_str().to_lowercase().chars())
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("T{}", name)
+    } else {
+        name
+    }
+}
+
+/// converts a header such as `Order Total`, `unit-price` or `firstName` to snake_case
+/// (`order_total`). runs of characters that are not alphanumeric become a single underscore,
+/// and leading or trailing separators are dropped. a camelcase boundary also starts a new
+/// word: an uppercase letter after a lowercase letter or digit, or the last capital of an
+/// acronym followed by a lowercase letter (`HTTPServer` becomes `http_server`).
+pub fn to_snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.trim().chars().collect();
+    let mut name = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && i > 0 && !name.is_empty() && !name.ends_with('_') {
+                let prev = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+                if prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next_is_lower)
+                {
+                    name.push('_');
+                }
+            }
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
+
+/// converts a header such as `first name`, `first_name` or `first-name` to camelcase (`firstName`).
+/// any character that is not alphanumeric separates words.
+pub fn to_camel_case(s: &str) -> String {
+    s.split(|c: char| !c.is_alphanumeric())
         .filter(|word| !word.is_empty())
-        .map(|word| word[0..1].to_uppercase() + &word[1..].to_lowercase())
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if i == 0 {
+                lower
+            } else {
+                let mut chars = lower.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+        })
         .collect()
 }
 
+/// makes column names unique by appending `_2`, `_3`, ... to repeats of an earlier name, e.g.
+/// `id, name, id` becomes `id, name, id_2`. a suffixed name also skips names already in use.
+pub fn unique_names<I>(names: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let names: Vec<String> = names.into_iter().collect();
+    let mut used: HashSet<String> = HashSet::new();
+    let mut unique = Vec::with_capacity(names.len());
+    for (i, name) in names.iter().enumerate() {
+        let mut candidate = name.clone();
+        let mut n = 2;
+        // a later original name keeps priority over a generated suffix (`a, a, a_2`).
+        while used.contains(&candidate) || (candidate != *name && names[i..].contains(&candidate)) {
+            candidate = format!("{}_{}", name, n);
+            n += 1;
+        }
+        used.insert(candidate.clone());
+        unique.push(candidate);
+    }
+    unique
+}
+
+/// escapes a value as a json string literal, including the surrounding quotes.
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pascal_case() {
+        assert_eq!(to_pascal_case("user_accounts"), "UserAccounts");
+        assert_eq!(to_pascal_case("order-items"), "OrderItems");
+        assert_eq!(to_pascal_case("2023 sales!"), "T2023Sales");
+        assert_eq!(to_pascal_case("123"), "T123");
+        assert_eq!(to_pascal_case("my.data (v2)"), "MyDataV2");
+        assert_eq!(to_pascal_case("!!"), "T");
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("first name"), "firstName");
+        assert_eq!(to_camel_case("user_id"), "userId");
+        assert_eq!(to_camel_case("Updated-At"), "updatedAt");
+        assert_eq!(to_camel_case(" price "), "price");
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Order Total"), "order_total");
+        assert_eq!(to_snake_case(" unit--price? "), "unit_price");
+        assert_eq!(to_snake_case("__"), "");
+    }
+
+    #[test]
+    fn test_to_snake_case_splits_camel_case_and_punctuation() {
+        assert_eq!(to_snake_case("firstName"), "first_name");
+        assert_eq!(to_snake_case("First Name"), "first_name");
+        assert_eq!(to_snake_case("first.name"), "first_name");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("userID"), "user_id");
+        assert_eq!(to_snake_case("address2Line"), "address2_line");
+        assert_eq!(to_snake_case("SKU"), "sku");
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let unique = |names: &[&str]| unique_names(names.iter().map(|name| name.to_string()));
+        assert_eq!(unique(&["id", "name", "id"]), ["id", "name", "id_2"]);
+        assert_eq!(unique(&["a", "a", "a"]), ["a", "a_2", "a_3"]);
+        assert_eq!(unique(&["a", "a", "a_2"]), ["a", "a_3", "a_2"]);
+        assert_eq!(unique(&["a_2", "a", "a"]), ["a_2", "a", "a_3"]);
+    }
+
+    #[test]
+    fn test_json_string_escapes_control_characters() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::io::{self, Read};
+
+use csv::StringRecord;
+
+use crate::{InferenceOptions, map_csv_error, open_csv};
+
+/// a record whose number of fields differs from the header row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaggedRecord {
+    /// the index of the record, counting the header row as record 0 (as in the errors of
+    /// `infer_schema`).
+    pub record: u64,
+    /// the line the record starts on, from 1.
+    pub line: u64,
+    /// the number of fields in the record.
+    pub len: usize,
+}
+
+/// the outcome of `validate_csv`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// the number of data records, not counting the header row.
+    pub row_count: usize,
+    /// the number of columns, as given by the header row (or the first record without one).
+    pub column_count: usize,
+    /// every record with a different number of fields than `column_count`, in input order.
+    pub ragged_records: Vec<RaggedRecord>,
+}
+
+impl ValidationReport {
+    /// returns true if every record has `column_count` fields.
+    pub fn is_valid(&self) -> bool {
+        self.ragged_records.is_empty()
+    }
+}
+
+/// the report as printed by `--validate`: a summary line, then one line per ragged record.
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rows, {} columns", self.row_count, self.column_count)?;
+        if self.is_valid() {
+            return write!(f, ": ok");
+        }
+        write!(f, ", {} ragged rows", self.ragged_records.len())?;
+        for ragged in &self.ragged_records {
+            write!(
+                f,
+                "\nrecord {} (line {}) has {} fields, expected {}",
+                ragged.record, ragged.line, ragged.len, self.column_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// checks that the csv is well-formed without inferring any types: every record is read (the
+/// `sample_rows` limit does not apply) and those with an unexpected number of fields are
+/// reported instead of failing. other problems, such as invalid utf-8, are still errors.
+pub fn validate_csv<R: Read>(
+    reader: R,
+    options: &InferenceOptions,
+) -> io::Result<ValidationReport> {
+    // flexible, so a ragged record is yielded to be reported rather than failing the read.
+    let options = InferenceOptions {
+        flexible: true,
+        ..options.clone()
+    };
+    let (headers, mut rdr) = open_csv(reader, &options)?;
+
+    let mut report = ValidationReport {
+        row_count: 0,
+        column_count: headers.len(),
+        ragged_records: Vec::new(),
+    };
+    let mut record = StringRecord::new();
+    while rdr.read_record(&mut record).map_err(map_csv_error)? {
+        report.row_count += 1;
+        if record.len() != report.column_count {
+            let position = record.position();
+            report.ragged_records.push(RaggedRecord {
+                record: position.map_or(0, |pos| pos.record()),
+                line: position.map_or(0, |pos| pos.line()),
+                len: record.len(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_validate_ragged_csv() {
+        let csv_data = "a,b,c\n1,2,3\n4,5\n6,7,8\n9,10,11,12\n";
+        let report = validate_csv(Cursor::new(csv_data), &InferenceOptions::default()).unwrap();
+
+        assert_eq!(
+            report,
+            ValidationReport {
+                row_count: 4,
+                column_count: 3,
+                ragged_records: vec![
+                    RaggedRecord {
+                        record: 2,
+                        line: 3,
+                        len: 2
+                    },
+                    RaggedRecord {
+                        record: 4,
+                        line: 5,
+                        len: 4
+                    },
+                ],
+            }
+        );
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.to_string(),
+            "4 rows, 3 columns, 2 ragged rows\nrecord 2 (line 3) has 2 fields, expected 3\n\
+             record 4 (line 5) has 4 fields, expected 3"
+        );
+    }
+
+    #[test]
+    fn test_validate_well_formed_csv() {
+        let options = InferenceOptions::default().with_sample_rows(Some(1));
+        let report = validate_csv(Cursor::new("id,name\n1,a\n2,b\n"), &options).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.to_string(), "2 rows, 2 columns: ok");
+    }
+}
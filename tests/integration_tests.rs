@@ -1,4 +1,9 @@
-use csv_sql_inference::{generate_sql, infer_schema, SqlType};
+use csv_sql_inference::python_generator::{PkStrategy, PythonTarget};
+use csv_sql_inference::{
+    enum_check_values, generate_sql, generate_sql_with_options, generate_sqlmodel_python,
+    infer_schema, infer_schema_with_options, infer_schema_with_stats, Dialect, InferenceOptions,
+    SqlOptions, SqlType,
+};
 use std::io::Cursor;
 
 #[test]
@@ -115,4 +120,248 @@ fn test_table_name_generation_from_main_logic() {
         .and_then(|s| s.to_str())
         .unwrap_or("default_table");
     assert_eq!(table_name_derived_no_ext, "my_other_data");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_headerless_csv_names_flow_into_generators() {
+    let csv_data = "1,Alice\n2,Bob";
+    let reader = Cursor::new(csv_data);
+    let options = InferenceOptions { has_headers: false, ..Default::default() };
+    let (headers, types) = infer_schema_with_options(reader, &options).expect("schema inference failed for headerless csv");
+
+    assert_eq!(headers.iter().collect::<Vec<&str>>(), &["col_1", "col_2"][..]);
+
+    let sql = generate_sql("people", &headers, &types);
+    let expected_sql = "CREATE TABLE \"people\" (\n  \"col_1\" INTEGER,\n  \"col_2\" VARCHAR(5)\n);";
+    assert_eq!(sql, expected_sql);
+
+    let python = generate_sqlmodel_python(
+        "people",
+        &headers,
+        &types,
+        &[true, true],
+        &PkStrategy::ExistingColumn("col_1".to_string()),
+        PythonTarget::default(),
+    );
+    assert!(python.contains("    col_1: int | None = Field(default=None, primary_key=True)\n"));
+    assert!(python.contains("    col_2: str | None = Field(default=None, max_length=5)\n"));
+}
+
+#[test]
+fn test_cli_verbose_logs_to_stderr_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("people.csv");
+    std::fs::write(&path, "id,name\n1,Alice\n2,Bob\n3,\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .arg("--verbose")
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "CREATE TABLE \"people\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(5)\n);\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("read 3 records with 2 columns"));
+    assert!(stderr.contains("column \"name\": Varchar(5) (1 empty values)"));
+
+    let quiet = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .arg("--quiet")
+        .output()
+        .expect("failed to run the cli");
+    assert!(quiet.stderr.is_empty());
+}
+
+#[test]
+fn test_status_column_check_constraint() {
+    let csv_data = "id,status\n1,open\n2,won't fix\n3,open\n4,closed\n5,\n";
+    let options = InferenceOptions::default();
+    let (headers, stats) = infer_schema_with_stats(Cursor::new(csv_data), &options).unwrap();
+    let types: Vec<SqlType> = stats.iter().map(|stats| stats.sql_type.clone()).collect();
+    let sql_options = SqlOptions {
+        check_values: enum_check_values(&headers, &stats),
+        ..SqlOptions::default()
+    };
+
+    let sql = generate_sql_with_options("tickets", &headers, &types, Dialect::Generic, &sql_options);
+    assert_eq!(
+        sql,
+        "CREATE TABLE \"tickets\" (\n  \"id\" INTEGER,\n  \"status\" VARCHAR(9) CHECK (\"status\" IN \
+         ('open', 'won''t fix', 'closed'))\n);"
+    );
+    // without the option the ddl is unchanged
+    assert_eq!(
+        generate_sql_with_options("tickets", &headers, &types, Dialect::Generic, &SqlOptions::default()),
+        generate_sql("tickets", &headers, &types)
+    );
+}
+
+#[test]
+fn test_cli_validate_exits_non_zero_on_ragged_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ragged.csv");
+    std::fs::write(&path, "a,b\n1,2\n3\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .arg("--validate")
+        .output()
+        .expect("failed to run the cli");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2 rows, 2 columns, 1 ragged rows\nrecord 2 (line 3) has 1 fields, expected 2\n"
+    );
+
+    std::fs::write(&path, "a,b\n1,2\n").unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .arg("--validate")
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1 rows, 2 columns: ok\n");
+}
+
+#[test]
+fn test_cli_headerless_file_with_column_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("people.csv");
+    std::fs::write(&path, "1,Alice\n2,Bob\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .args(["--no-header", "--columns", "id,name"])
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "CREATE TABLE \"people\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(5)\n);\n"
+    );
+}
+
+#[test]
+fn test_cli_char_and_varchar_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rates.csv");
+    std::fs::write(&path, "country,currency\nFR,EUR\nNO,NOK\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .args(["--varchar", "country"])
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "CREATE TABLE \"rates\" (\n  \"country\" VARCHAR(2),\n  \"currency\" CHAR(3)\n);\n"
+    );
+}
+
+#[test]
+fn test_cli_infers_every_csv_of_a_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("users.csv"), "id,name\n10,Alice\n20,Bob\n").unwrap();
+    std::fs::write(dir.path().join("orders.csv"), "id,user_id,total\n1,10,9.5\n2,20,12\n").unwrap();
+    std::fs::write(dir.path().join("broken.csv"), "a,b\n1,2,3\n").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not,a\ntable,at all\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg("--dir")
+        .arg(dir.path())
+        .output()
+        .expect("failed to run the cli");
+    // the broken file is reported and fails the run, but the others are still generated.
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("broken.csv"));
+    // orders.user_id refers to users, so users is created first.
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "CREATE TABLE \"users\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(5)\n);\n\n\
+         CREATE TABLE \"orders\" (\n  \"id\" INTEGER,\n  \"user_id\" INTEGER,\n  \"total\" FLOAT\n);\n"
+    );
+}
+
+#[test]
+fn test_cli_pk_column_matches_sanitized_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("orders.csv");
+    std::fs::write(&path, "order_id,total\n10,2.5\n11,3\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .args(["--pk-column", "Order ID"])
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("  PRIMARY KEY (\"order_id\")\n"));
+}
+
+#[test]
+fn test_cli_py_optional_applies_to_every_python_flavor() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("people.csv");
+    std::fs::write(&path, "id,name\n1,Alice\n2,\n").unwrap();
+
+    for flavor in ["--python", "--pydantic", "--dataclass"] {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+            .arg(&path)
+            .args([flavor, "--py-optional"])
+            .output()
+            .expect("failed to run the cli");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("from typing import Optional\n"), "{}", flavor);
+        assert!(stdout.contains("    name: Optional[str]"), "{}", flavor);
+    }
+}
+
+#[test]
+fn test_cli_snake_case_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("orders.csv");
+    std::fs::write(&path, "OrderID,Total\n1,2.5\n2,3\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+        .arg(&path)
+        .args(["--dataclass", "--snake-case-fields"])
+        .output()
+        .expect("failed to run the cli");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("    order_id: int | None = None\n"));
+    assert!(stdout.contains("    total: float | None = None\n"));
+}
+
+#[test]
+fn test_cli_reads_csv_from_stdin() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    for args in [&["-"][..], &["--table-name", "people"][..]] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_csv_sql_inference"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to run the cli");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"id,name\n1,Alice\n2,Bob\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let table_name = if args == ["-"] { "my_table" } else { "people" };
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("CREATE TABLE \"{}\" (\n  \"id\" INTEGER,\n  \"name\" VARCHAR(5)\n);\n", table_name)
+        );
+    }
+}